@@ -12,26 +12,60 @@
 //! # Implementation Details
 //! - The original solution (+some improvements) is in the main module;
 //! - A second aproach using a more elegant way to structure the code is given in the module `second_implementation`
+//! - `run_by_intersection` counts the overlaps without any grid, by intersecting each pair of lines.
 
 extern crate test;
 
+use std::{collections::HashSet, iter};
+
 use crate::helpers::{
     base2d::Base2d,
-    grid::Grid,
-    read,
-    utils::{self, delta::Delta},
+    grid::{Grid, Grid2d},
+    raster, read,
+    rect::Rect,
+    segment::Segment,
+    sparse_grid::SparseGrid,
+    utils,
 };
 
+/// Largest amount of cells for which a dense `Grid` is allocated, larger fields are stored in a `SparseGrid`.
+const DENSE_LIMIT: usize = 1 << 24;
+
 pub fn run() {
     let input: Vec<_> = parse_input().collect();
 
-    // calculates max lenghs to create a grid
-    let (len_x, len_y) = input.iter().fold((0, 0), |(lx, ly), b| (lx.max(b.x), ly.max(b.y)));
+    // the grid starts at the origin and must contain every point
+    let bounds = Rect::from_points(iter::once(Base2d::new(0, 0)).chain(input.iter().copied())).unwrap();
+    // lengths in `u64` so they do not overflow for the largest coordinates, a product that overflows goes sparse
+    let (len_x, len_y) = (u64::from(bounds.max.x) + 1, u64::from(bounds.max.y) + 1);
+    let is_dense = len_x
+        .checked_mul(len_y)
+        .is_some_and(|cells| cells <= DENSE_LIMIT as u64);
+    let (count_overlaps_straight, count_overlaps_all) = if is_dense {
+        let mut grid = Grid::new(len_x as usize, len_y as usize, 0u16);
+        (
+            overlaps_straight_lines(&mut grid, &input),
+            overlaps_diagonal_lines(&mut grid, &input),
+        )
+    } else {
+        let mut grid = SparseGrid::new(0u16);
+        (
+            overlaps_straight_lines(&mut grid, &input),
+            overlaps_diagonal_lines(&mut grid, &input),
+        )
+    };
+
+    println!("Day 05");
+    println!("Count of overlaps for straight lines: {}", count_overlaps_straight);
+    println!("Count of overlaps for all lines: {}", count_overlaps_all);
+    println!();
+}
 
-    // +1 because initial position is (0, 0)
-    let mut grid = Grid::new((len_x + 1) as usize, (len_y + 1) as usize, 0u16);
-    let count_overlaps_straight = overlaps_straight_lines(&mut grid, &input);
-    let count_overlaps_all = overlaps_diagonal_lines(&mut grid, &input);
+/// Same solution without any grid, so the memory does not depend on the size of the field.
+pub fn run_by_intersection() {
+    let input: Vec<_> = parse_input().collect();
+    let count_overlaps_straight = overlaps_by_intersection(&input, false);
+    let count_overlaps_all = overlaps_by_intersection(&input, true);
 
     println!("Day 05");
     println!("Count of overlaps for straight lines: {}", count_overlaps_straight);
@@ -42,31 +76,64 @@ pub fn run() {
 /// Fills a grid using only straight lines and returns the number of positions where at least two lines intercept.
 ///
 /// #Inputs
-/// `grid`: a `Grid` which uses `u16` variables an a (x, y) coordinate systems.
+/// `grid`: a `Grid` or `SparseGrid` which uses `u16` variables an a (x, y) coordinate systems.
 /// `input`: a list of points `Base2d` where each two of them represents a line.
-fn overlaps_straight_lines(grid: &mut Grid<u16>, input: &[Base2d<u16>]) -> usize {
+fn overlaps_straight_lines<G: Grid2d<u16>>(grid: &mut G, input: &[Base2d<u32>]) -> usize {
     fill_grid(grid, input, false);
-    grid.iter().filter(|&&v| v > 1).count()
+    grid.count_where(|&v| v > 1)
 }
 
 /// Fills a grid using only diagonal lines and returns the number of positions where at least two lines intercept.
 ///
 /// #Inputs
-/// `grid`: a `Grid` which uses `u16` variables an a (x, y) coordinate systems.
+/// `grid`: a `Grid` or `SparseGrid` which uses `u16` variables an a (x, y) coordinate systems.
 /// `input`: a list of points `Base2d` where each two of them represents a line.
-fn overlaps_diagonal_lines(grid: &mut Grid<u16>, input: &[Base2d<u16>]) -> usize {
+fn overlaps_diagonal_lines<G: Grid2d<u16>>(grid: &mut G, input: &[Base2d<u32>]) -> usize {
     fill_grid(grid, input, true);
-    grid.iter().filter(|&&v| v > 1).count()
+    grid.count_where(|&v| v > 1)
+}
+
+/// Returns the number of positions where at least two lines intercept, computed from the intersections between each
+/// pair of lines, so no grid is needed. `include_diagonal` adds the lines of any other slope, same as `fill_grid`.
+///
+/// Straight and 45° lines are intersected exactly. Lines of other slopes cover the positions rasterised by
+/// `fill_diagonal` instead of their exact lattice points, so they are intersected through those positions and the
+/// result matches the grid solution.
+///
+/// The time depends on the amount of lines instead of the size of the field, it is quadratic though.
+fn overlaps_by_intersection(input: &[Base2d<u32>], include_diagonal: bool) -> usize {
+    let lines: Vec<_> = utils::pairs_zip(input)
+        .filter(|(p0, p1)| include_diagonal || p0.is_same_column(p1) || p0.is_same_row(p1))
+        .map(|(p0, p1)| {
+            let segment = Segment::from((*p0, *p1));
+            let rasterised = (!segment.is_straight() && !segment.is_diagonal())
+                .then(|| rasterise_line(p0, p1).collect::<HashSet<_>>());
+            (segment, rasterised)
+        })
+        .collect();
+
+    let mut overlaps = HashSet::new();
+    for (i, (s0, r0)) in lines.iter().enumerate() {
+        for (s1, r1) in &lines[i + 1..] {
+            match (r0, r1) {
+                (None, None) => overlaps.extend(s0.intersection(s1)),
+                (Some(r0), None) => overlaps.extend(r0.iter().filter(|p| s1.contains(p))),
+                (None, Some(r1)) => overlaps.extend(r1.iter().filter(|p| s0.contains(p))),
+                (Some(r0), Some(r1)) => overlaps.extend(r0.intersection(r1)),
+            }
+        }
+    }
+    overlaps.len()
 }
 
 /// returns an iterator yielding a point
-fn parse_input() -> impl Iterator<Item = Base2d<u16>> {
+fn parse_input() -> impl Iterator<Item = Base2d<u32>> {
     let input = read::file_to_lines("day05").unwrap();
 
     input
         .flatten()
         .map(|s| {
-            let inner: Vec<_> = s.split(" -> ").map(str::parse::<Base2d<u16>>).flatten().collect();
+            let inner: Vec<_> = s.split(" -> ").map(str::parse::<Base2d<u32>>).flatten().collect();
             inner.into_iter()
         })
         .flatten()
@@ -76,10 +143,10 @@ fn parse_input() -> impl Iterator<Item = Base2d<u16>> {
 // Helpers
 //--------------------------------------------------------------------
 
-/// method for filling the grid by straight lines (columns and rows) or diagonal lines (at 45°).
+/// method for filling the grid by straight lines (columns and rows) or diagonal lines (at 45° or any other slope).
 /// Each pair at the `input` list defines a line and `go_diagonal` flag defines if the function will
 /// fill the grid by the diagonal lines (`true`) or straight lines (`false`).
-fn fill_grid(grid: &mut Grid<u16>, input: &[Base2d<u16>], go_diagonal: bool) {
+fn fill_grid<G: Grid2d<u16>>(grid: &mut G, input: &[Base2d<u32>], go_diagonal: bool) {
     let lines = utils::pairs_zip(input);
     for (p0, p1) in lines {
         if !go_diagonal {
@@ -96,36 +163,31 @@ fn fill_grid(grid: &mut Grid<u16>, input: &[Base2d<u16>], go_diagonal: bool) {
 
 /// helper method to update a column line in the grid based on the coordinates given by p0 and p1.
 /// The x coordinate from p0 is soleny used the determine the column.
-fn fill_column(grid: &mut Grid<u16>, p0: &Base2d<u16>, p1: &Base2d<u16>) {
+fn fill_column<G: Grid2d<u16>>(grid: &mut G, p0: &Base2d<u32>, p1: &Base2d<u32>) {
     let x = p0.x;
     let (y0, y1) = utils::min_max(p0.y, p1.y);
-    (y0..=y1).for_each(|y| *grid.get_mut(x.into(), y.into()) += 1);
+    (y0..=y1).for_each(|y| *grid.get_mut(x as usize, y as usize) += 1);
 }
 
 /// helper method to update a row line in the grid based on the coordinates given by p0 and p1. The y coordinate from p0
 /// is soleny used the determine the column.
-fn fill_row(grid: &mut Grid<u16>, p0: &Base2d<u16>, p1: &Base2d<u16>) {
+fn fill_row<G: Grid2d<u16>>(grid: &mut G, p0: &Base2d<u32>, p1: &Base2d<u32>) {
     let y = p0.y;
     let (x0, x1) = utils::min_max(p0.x, p1.x);
-    (x0..=x1).for_each(|x| *grid.get_mut(x.into(), y.into()) += 1);
+    (x0..=x1).for_each(|x| *grid.get_mut(x as usize, y as usize) += 1);
 }
 
-/// helper method to update diagonal lines in the grid (diagonals at 45°), based on the coordinates given by points p0
-/// and p1.
-fn fill_diagonal(grid: &mut Grid<u16>, p0: &Base2d<u16>, p1: &Base2d<u16>) {
-    let dx = Delta::new(p0.x, p1.x, 1);
-    let dy = Delta::new(p0.y, p1.y, 1);
-
-    let (mut x, mut y) = p0.tuple();
-    *grid.get_mut(x.into(), y.into()) += 1;
-    loop {
-        x += dx;
-        y += dy;
-        *grid.get_mut(x.into(), y.into()) += 1;
-        if (x, y) == p1.tuple() {
-            break;
-        }
-    }
+/// helper method to update diagonal lines in the grid, based on the coordinates given by points p0 and p1. Lines of any
+/// slope are rasterised, not only diagonals at 45°.
+fn fill_diagonal<G: Grid2d<u16>>(grid: &mut G, p0: &Base2d<u32>, p1: &Base2d<u32>) {
+    rasterise_line(p0, p1).for_each(|p| *grid.get_mut(p.x as usize, p.y as usize) += 1);
+}
+
+/// returns an iterator over the positions of the line between p0 and p1.
+fn rasterise_line(p0: &Base2d<u32>, p1: &Base2d<u32>) -> raster::Bresenham {
+    // rasterising always from the leftmost point makes the result independent of the line direction
+    let (p0, p1) = if p0.tuple() <= p1.tuple() { (p0, p1) } else { (p1, p0) };
+    raster::line(p0, p1)
 }
 
 //--------------------------------------------------------------------
@@ -134,9 +196,9 @@ fn fill_diagonal(grid: &mut Grid<u16>, p0: &Base2d<u16>, p1: &Base2d<u16>) {
 
 pub mod second_implementation {
     use itertools::{Itertools, Tuples};
-    use std::{error::Error, iter::Copied, slice::Iter, str::FromStr};
+    use std::{error::Error, iter, iter::Copied, slice::Iter, str::FromStr};
 
-    use crate::helpers::{base2d::Base2d, grid::Grid, read, utils::delta::Delta};
+    use crate::helpers::{base2d::Base2d, grid::Grid, raster, read, rect::Rect};
 
     //-----------------
     // Type Definitions
@@ -150,7 +212,7 @@ pub mod second_implementation {
     #[derive(PartialEq)]
     enum LinePattern {
         All,        // any type
-        Diagonal,   // 45° diagonal lines
+        Diagonal,   // diagonal lines of any slope
         Horizontal, // Same row
         Straight,   // Horizontal or Vertical
         Vertical,   // Same column
@@ -163,11 +225,9 @@ pub mod second_implementation {
     pub fn run() {
         let lines: Lines = read::file_to_string("day05").unwrap().parse().unwrap();
 
-        // calculates max lenghs to create a grid
-        let (len_x, len_y) = lines.max_dimensions();
-
-        // +1 because initial position is (0, 0)
-        let mut grid = Grid::new((len_x + 1) as usize, (len_y + 1) as usize, 0u16);
+        // the grid starts at the origin and must contain every point
+        let bounds = lines.bounds();
+        let mut grid = Grid::new(bounds.width() as usize, bounds.height() as usize, 0u16);
         let count_overlaps_straight = overlap_lines(&mut grid, &lines, LinePattern::Straight);
         let count_overlaps_all = overlap_lines(&mut grid, &lines, LinePattern::Diagonal);
 
@@ -200,29 +260,19 @@ pub mod second_implementation {
             .filter(filter_criteria)
             .for_each(|line| update_grid_line(grid, line));
 
-        grid.iter().filter(|&&v| v > 1).count()
+        grid.count_where(|&v| v > 1)
     }
 
     //-----------------
     // Helper Functions
     //-----------------
 
-    /// helper function to update the posions under a given line in the grid (vertical, horizontal or diagonals at 45°).
+    /// helper function to update the posions under a given line in the grid (vertical, horizontal or diagonals of any
+    /// slope).
     fn update_grid_line(grid: &mut Grid<u16>, line: Line) {
         let (p0, p1) = line;
-        let dx = Delta::new(p0.x, p1.x, if p0.is_same_column(&p1) { 0 } else { 1 });
-        let dy = Delta::new(p0.y, p1.y, if p0.is_same_row(&p1) { 0 } else { 1 });
-
-        let (mut x, mut y) = p0.tuple();
-        *grid.get_mut(x.into(), y.into()) += 1;
-        loop {
-            x += dx;
-            y += dy;
-            *grid.get_mut(x.into(), y.into()) += 1;
-            if (x, y) == p1.tuple() {
-                break;
-            }
-        }
+        let (p0, p1) = if p0.tuple() <= p1.tuple() { (p0, p1) } else { (p1, p0) };
+        raster::line(&p0, &p1).for_each(|p| *grid.get_mut(p.x as usize, p.y as usize) += 1);
     }
 
     //-----------------
@@ -230,9 +280,9 @@ pub mod second_implementation {
     //-----------------
 
     impl Lines {
-        /// reads all lines and returns the x and y boundaries.
-        fn max_dimensions(&self) -> (u16, u16) {
-            self.0.iter().fold((0, 0), |(lx, ly), b| (lx.max(b.x), ly.max(b.y)))
+        /// reads all lines and returns the rectangle from the origin to the farthest point.
+        fn bounds(&self) -> Rect<u16> {
+            Rect::from_points(iter::once(Base2d::new(0, 0)).chain(self.0.iter().copied())).unwrap()
         }
     }
 
@@ -281,7 +331,7 @@ mod tests {
     use test::Bencher;
 
     lazy_static! {
-        static ref TEST_INPUT: Vec<Base2d<u16>> = vec![
+        static ref TEST_INPUT: Vec<Base2d<u32>> = vec![
             Base2d::new(0, 9),
             Base2d::new(5, 9),
             Base2d::new(8, 0),
@@ -314,6 +364,44 @@ mod tests {
         assert_eq!(ans2, 12);
     }
 
+    #[test]
+    fn test_overlaps_any_slope() {
+        // overlaps at (2, 1), (2, 2), (3, 2) and (4, 2), the shallow lines are rasterised from their leftmost point
+        let input = vec![
+            Base2d::new(0, 0),
+            Base2d::new(6, 3),
+            Base2d::new(2, 0),
+            Base2d::new(2, 2),
+            Base2d::new(7, 0),
+            Base2d::new(1, 3),
+            Base2d::new(4, 2),
+            Base2d::new(4, 3),
+        ];
+        let mut grid = Grid::new(8, 4, 0u16);
+        assert_eq!(overlaps_straight_lines(&mut grid, &input), 0);
+        assert_eq!(overlaps_diagonal_lines(&mut grid, &input), 4);
+        assert_eq!(overlaps_by_intersection(&input, false), 0);
+        assert_eq!(overlaps_by_intersection(&input, true), 4);
+    }
+
+    #[test]
+    fn test_overlaps_by_intersection() {
+        assert_eq!(overlaps_by_intersection(&TEST_INPUT, false), 5);
+        assert_eq!(overlaps_by_intersection(&TEST_INPUT, true), 12);
+    }
+
+    #[test]
+    fn test_overlaps_sparse() {
+        // same lines as the test input, moved far away from the origin
+        let offset = Base2d::new(400_000, 900_000);
+        let input: Vec<_> = TEST_INPUT.iter().map(|&p| p + offset).collect();
+        let mut grid = SparseGrid::new(0u16);
+        let ans1 = overlaps_straight_lines(&mut grid, &input);
+        assert_eq!(ans1, 5);
+        let ans2 = overlaps_diagonal_lines(&mut grid, &input);
+        assert_eq!(ans2, 12);
+    }
+
     //-----------------
     // Benches
     //-----------------
@@ -13,17 +13,23 @@
 //!
 //! - Uses a naive solution for part 1 where each new fish is added as a new element in a `Vec`;
 //!
-//! - For part 2, uses a circular buffer where the positions represent the ages whereas the contents are the numbers of
-//!   fish. An index marks which position holds the fish at 0 days to reproduce.
+//! - For part 2, uses a circular buffer (`RingBuffer`) where the positions represent the ages whereas the contents are
+//!   the numbers of fish. Rotating the buffer ages every fish by one day;
+//!
+//! - `run_matrix` computes the same result for any amount of days in `O(log days)`, raising the matrix of one day of
+//!   transitions to the amount of days;
+//!
+//! - The counts of the school are generic, with `BigUint` instead of `u64` the population can be simulated exactly for
+//!   thousands of days, as done by `run_exact`.
 
-use std::{error::Error, str::FromStr};
+use std::{convert::TryFrom, error::Error, iter::Sum, ops::AddAssign, str::FromStr};
 
-use crate::helpers::read;
+use crate::helpers::{bigint::BigUint, matrix::Matrix, read, ring_buffer::RingBuffer};
 
 pub fn run() {
     let mut fishes = parse_input();
     let ans_pt1 = part01(&mut fishes, 80);
-    let mut fish_school = parse_input_pt2();
+    let mut fish_school: SchoolOfLanternfish = parse_input_pt2();
     let ans_pt2 = part02(&mut fish_school, 256);
 
     println!("Day 06");
@@ -31,6 +37,35 @@ pub fn run() {
     println!("Fish schools size after 256 days: {}", ans_pt2);
 }
 
+/// Solution for part 2 using matrix exponentiation, it also computes the population after a trillion days, modulo a
+/// prime.
+pub fn run_matrix() {
+    const DAYS: u64 = 1_000_000_000_000;
+    const MODULUS: u64 = 1_000_000_007;
+
+    let fish_school = parse_input_pt2();
+    let ans_pt2 = part02_matrix(&fish_school, 256, None);
+    let ans_long = part02_matrix(&fish_school, DAYS, Some(MODULUS));
+
+    println!("Day 06");
+    println!("Fish schools size after 256 days: {}", ans_pt2);
+    println!(
+        "Fish schools size after {} days, modulo {}: {}",
+        DAYS, MODULUS, ans_long
+    );
+}
+
+/// Solution for part 2 counting the fishes with `BigUint`, so the population is exact even far beyond `u64`.
+pub fn run_exact() {
+    const DAYS: usize = 2048;
+
+    let mut fish_school: SchoolOfLanternfish<BigUint> = parse_input_pt2();
+    let ans = part02(&mut fish_school, DAYS);
+
+    println!("Day 06");
+    println!("Fish schools size after {} days: {}", DAYS, ans);
+}
+
 /// Helper to parse the input file for part 1 solution, returns a `Vec` where each element is a fish.
 fn parse_input() -> Vec<Lanternfish> {
     let input = read::file_to_string("day06").unwrap();
@@ -40,7 +75,7 @@ fn parse_input() -> Vec<Lanternfish> {
 
 /// Helper to parse the input file for part 2 solution, the output is a struct `SchoolOfLanternfish` which models the
 /// behaviour of the group.
-fn parse_input_pt2() -> SchoolOfLanternfish {
+fn parse_input_pt2<T: Clone + AddAssign + From<u64>>() -> SchoolOfLanternfish<T> {
     let input = read::file_to_string("day06").unwrap();
     let input = input.lines().next().unwrap();
     input.parse().unwrap()
@@ -62,13 +97,33 @@ fn part01(fish_school: &mut Vec<Lanternfish>, days: usize) -> usize {
 }
 
 /// Solution for part 2 simulating the behaviour of the group.
-fn part02(fish_school: &mut SchoolOfLanternfish, days: usize) -> u64 {
+fn part02<T>(fish_school: &mut SchoolOfLanternfish<T>, days: usize) -> T
+where
+    T: Clone + AddAssign,
+    for<'a> T: Sum<&'a T>,
+{
     for _ in 1..days + 1 {
         fish_school.try_reproduce();
     }
     fish_school.school.iter().sum()
 }
 
+/// Solution for part 2 using matrix exponentiation, so the amount of `days` can be as large as needed. The population
+/// grows past `u64` after about 440 days, so a `modulus` may be given to get the population modulo it instead.
+fn part02_matrix(fish_school: &SchoolOfLanternfish, days: u64, modulus: Option<u64>) -> u64 {
+    let transition = SchoolOfLanternfish::transition_matrix();
+    let counts: Vec<_> = fish_school.school.iter().copied().collect();
+    let counts = match modulus {
+        Some(m) => transition.pow_mod(days, m).mul_vec(&counts, modulus),
+        None => transition.pow(days).mul_vec(&counts, None),
+    };
+    let total = counts.iter().map(|&c| c as u128).sum::<u128>();
+    match modulus {
+        Some(m) => (total % m as u128) as u64,
+        None => u64::try_from(total).expect("population overflows u64"),
+    }
+}
+
 //------------------------------
 // Lanternfish
 //------------------------------
@@ -101,39 +156,53 @@ impl FromStr for Lanternfish {
 // Fish School
 //------------------------------
 
-/// Models the school of Lanternfish as a circular buffer to simulate its reprodutive behaviour.
+/// Models the school of Lanternfish as a circular buffer to simulate its reprodutive behaviour. The type of the counts
+/// `T` only needs to be large enough for the population.
 #[derive(Copy, Clone, Debug)]
-struct SchoolOfLanternfish {
-    school: [u64; 9], // an array to use as a circular buffer and hold day 0 to day 8
-    idx0: usize,      // the index for day 0 in the buffer
+struct SchoolOfLanternfish<T = u64> {
+    school: RingBuffer<T, { SchoolOfLanternfish::<u64>::BUFFER_LEN }>, // amount of fishes from day 0 to day 8
 }
 
-impl SchoolOfLanternfish {
+impl<T: Clone + AddAssign> SchoolOfLanternfish<T> {
     const BUFFER_LEN: usize = 9;
     const RESET_DAY: usize = 6;
 
-    /// uses a circular buffer, `self::idx0` indicates where in the buffer is located the amount of
-    /// fishes at day 0. When `idx0` moves to the next position, the previous one becomes day 8.
-    /// Therefore, moving `idx0` already implements the counter for the newborn whereas the current
-    /// fishes needs to be moved to day 6.
+    /// rotating the buffer moves every fish one day closer to reproduce, whereas the fishes at day 0 go to day 8.
+    /// Therefore, the rotation already implements the counter for the newborn whereas the parents need to be added
+    /// to day 6.
     fn try_reproduce(&mut self) {
-        let curr = self.school[self.idx0];
-        self.idx0 = (self.idx0 + 1) % Self::BUFFER_LEN; // updates current day 0 idx
-        let reset_idx = (self.idx0 + Self::RESET_DAY) % Self::BUFFER_LEN; // define reset idx
-        self.school[reset_idx] += curr; // adds the reseted fishes to reset day
+        self.school.rotate_left(1);
+        let parents = self.school[Self::BUFFER_LEN - 1].clone();
+        self.school[Self::RESET_DAY] += parents; // adds the reseted fishes to reset day
+    }
+}
+
+impl SchoolOfLanternfish {
+    /// returns the matrix which maps the amount of fishes at each day to the amounts on the next day, the same
+    /// transition done by `try_reproduce`.
+    fn transition_matrix() -> Matrix {
+        let mut m = Matrix::new(Self::BUFFER_LEN, Self::BUFFER_LEN);
+        for day in 0..Self::BUFFER_LEN - 1 {
+            m[(day, day + 1)] = 1; // every fish gets one day closer
+        }
+        m[(Self::BUFFER_LEN - 1, 0)] = 1; // newborns
+        m[(Self::RESET_DAY, 0)] = 1; // parents
+        m
     }
 }
 
-impl FromStr for SchoolOfLanternfish {
+impl<T: Clone + AddAssign + From<u64>> FromStr for SchoolOfLanternfish<T> {
     type Err = Box<dyn Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut school = [0u64; Self::BUFFER_LEN];
+        let mut school = [0u64; SchoolOfLanternfish::<u64>::BUFFER_LEN];
         let fish_timers = s.split(',').flat_map(str::parse::<usize>);
         for t in fish_timers {
             school[t] += 1;
         }
-        Ok(Self { school, idx0: 0 })
+        Ok(Self {
+            school: RingBuffer::from(school.map(T::from)),
+        })
     }
 }
 
@@ -171,4 +240,37 @@ mod tests {
         assert_eq!(part02(&mut fish_school, 62), 5934); // total = 80 days
         assert_eq!(part02(&mut fish_school, 176), 26984457539); // total = 256 days
     }
+
+    #[test]
+    fn test_part02_exact() {
+        let mut fish_school: SchoolOfLanternfish<BigUint> = "3,4,3,1,2".parse().unwrap();
+        assert_eq!(part02(&mut fish_school, 256), BigUint::from(26984457539));
+
+        // far beyond `u64`, checked against the modular matrix solution
+        let population = part02(&mut fish_school, 4744); // total = 5000 days
+        assert_eq!(population.to_string().len(), 190);
+        let m = 1_000_000_007;
+        assert_eq!(
+            population.div_rem_u32(m).1 as u64,
+            part02_matrix(&TEST_INPUT2, 5000, Some(m as u64))
+        );
+    }
+
+    #[test]
+    fn test_part02_matrix() {
+        assert_eq!(part02_matrix(&TEST_INPUT2, 18, None), 26);
+        assert_eq!(part02_matrix(&TEST_INPUT2, 80, None), 5934);
+        assert_eq!(part02_matrix(&TEST_INPUT2, 256, None), 26984457539);
+
+        // the modular result matches the stepwise simulation reduced at each day
+        let m = 1_000_000_007;
+        let mut school = *TEST_INPUT2;
+        for _ in 0..1000 {
+            school.try_reproduce();
+            school.school.iter_mut().for_each(|c| *c %= m);
+        }
+        let expected = school.school.iter().sum::<u64>() % m;
+        assert_eq!(part02_matrix(&TEST_INPUT2, 1000, Some(m)), expected);
+        assert!(part02_matrix(&TEST_INPUT2, 1_000_000_000_000, Some(m)) < m);
+    }
 }
@@ -0,0 +1,212 @@
+//! A cellular automaton runner over a `Grid`.
+//!
+//! Every step computes the new value of each cell from its 3x3 neighbourhood, using a `rule` closure. The window is
+//! given in reading order, so the cell itself is at index 4:
+//!
+//! ```text
+//! 0 1 2
+//! 3 4 5
+//! 6 7 8
+//! ```
+//!
+//! The new values are written into a second buffer which is then swapped with the current one, so all cells are
+//! updated at once.
+
+use std::mem;
+
+use super::grid::Grid;
+
+/// Defines how the cells outside of the grid are read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edges<T> {
+    /// cells outside of the grid always hold the given value.
+    Bordered(T),
+    /// opposite sides of the grid are connected.
+    Wrapped,
+    /// the grid grows one cell on each side every step. Cells outside of it hold the given background value, which is
+    /// also updated by the rule.
+    Infinite(T),
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton<T> {
+    grid: Grid<T>,
+    back: Grid<T>, // buffer receiving the next generation
+    edges: Edges<T>,
+    generation: usize,
+}
+
+impl<T: Clone + PartialEq> Automaton<T> {
+    pub fn new(grid: Grid<T>, edges: Edges<T>) -> Automaton<T> {
+        Automaton {
+            back: grid.clone(),
+            grid,
+            edges,
+            generation: 0,
+        }
+    }
+
+    /// applies the `rule` to every cell once and returns how many cells changed value.
+    ///
+    /// For `Edges::Infinite`, the background is updated as well but it is not accounted in the returned count.
+    pub fn step<F>(&mut self, mut rule: F) -> usize
+    where
+        F: FnMut(&[T; 9]) -> T,
+    {
+        let mut next_background = None;
+        if let Edges::Infinite(background) = &self.edges {
+            self.grid = self.grid.padded((1, 1), (1, 1), background.clone());
+            self.back = self.grid.clone();
+            next_background = Some(rule(&std::array::from_fn(|_| background.clone())));
+        }
+
+        let mut changed = 0;
+        for y in 0..self.grid.len_y {
+            for x in 0..self.grid.len_x {
+                let value = rule(&self.window(x, y));
+                if value != *self.grid.get(x, y) {
+                    changed += 1;
+                }
+                self.back.update_at(x, y, value);
+            }
+        }
+
+        if let (Edges::Infinite(background), Some(next)) = (&mut self.edges, next_background) {
+            *background = next;
+        }
+        mem::swap(&mut self.grid, &mut self.back);
+        self.generation += 1;
+        changed
+    }
+
+    /// applies the `rule` `n` times and returns how many cells changed value in each step.
+    pub fn run<F>(&mut self, n: usize, mut rule: F) -> Vec<usize>
+    where
+        F: FnMut(&[T; 9]) -> T,
+    {
+        (0..n).map(|_| self.step(&mut rule)).collect()
+    }
+
+    /// applies the `rule` until a step does not change any cell and returns the number of steps taken, including the
+    /// last one. Returns `None` if no fixed point is found within `max_steps`.
+    pub fn run_until_stable<F>(&mut self, max_steps: usize, mut rule: F) -> Option<usize>
+    where
+        F: FnMut(&[T; 9]) -> T,
+    {
+        (1..=max_steps).find(|_| self.step(&mut rule) == 0)
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// returns the 3x3 neighbourhood centered on x,y.
+    fn window(&self, x: usize, y: usize) -> [T; 9] {
+        let cell = |dx: isize, dy: isize| -> T {
+            let (len_x, len_y) = (self.grid.len_x as isize, self.grid.len_y as isize);
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            match &self.edges {
+                Edges::Wrapped => self
+                    .grid
+                    .get(nx.rem_euclid(len_x) as usize, ny.rem_euclid(len_y) as usize)
+                    .clone(),
+                Edges::Bordered(outside) | Edges::Infinite(outside) => {
+                    if nx < 0 || ny < 0 || nx >= len_x || ny >= len_y {
+                        outside.clone()
+                    } else {
+                        self.grid.get(nx as usize, ny as usize).clone()
+                    }
+                }
+            }
+        };
+
+        [
+            cell(-1, -1),
+            cell(0, -1),
+            cell(1, -1),
+            cell(-1, 0),
+            cell(0, 0),
+            cell(1, 0),
+            cell(-1, 1),
+            cell(0, 1),
+            cell(1, 1),
+        ]
+    }
+}
+
+impl<T> Automaton<T> {
+    /// returns the current state of the grid.
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    /// returns the value of the cells outside of the grid, `None` for wrapped edges.
+    pub fn background(&self) -> Option<&T> {
+        match &self.edges {
+            Edges::Bordered(outside) | Edges::Infinite(outside) => Some(outside),
+            Edges::Wrapped => None,
+        }
+    }
+
+    /// returns how many steps have been applied.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Conway's Game of Life.
+    fn life(window: &[bool; 9]) -> bool {
+        let alive = window.iter().filter(|&&c| c).count() - window[4] as usize;
+        alive == 3 || (window[4] && alive == 2)
+    }
+
+    fn grid_from(s: &str) -> Grid<bool> {
+        Grid::from_lines(s, |c| c == '#')
+    }
+
+    #[test]
+    fn test_bordered() {
+        let blinker = grid_from(".....\n..#..\n..#..\n..#..\n.....\n");
+        let mut automaton = Automaton::new(blinker.clone(), Edges::Bordered(false));
+        assert_eq!(automaton.step(life), 4);
+        assert_eq!(*automaton.grid(), grid_from(".....\n.....\n.###.\n.....\n.....\n"));
+        assert_eq!(automaton.run(3, life), vec![4, 4, 4]);
+        assert_eq!(*automaton.grid(), blinker);
+
+        let block = grid_from("....\n.##.\n.##.\n....\n");
+        let mut automaton = Automaton::new(block, Edges::Bordered(false));
+        assert_eq!(automaton.run_until_stable(10, life), Some(1));
+    }
+
+    #[test]
+    fn test_wrapped() {
+        let glider = grid_from(".#...\n..#..\n###..\n.....\n.....\n");
+        let mut automaton = Automaton::new(glider.clone(), Edges::Wrapped);
+        automaton.run(20, life); // the glider moves one cell diagonally every 4 steps
+        assert_eq!(*automaton.grid(), glider);
+        assert_eq!(automaton.generation(), 20);
+    }
+
+    #[test]
+    fn test_infinite() {
+        let seed = grid_from("#\n");
+        let mut automaton = Automaton::new(seed, Edges::Infinite(false));
+        let any = |window: &[bool; 9]| window.iter().any(|&c| c);
+        assert_eq!(automaton.run(2, any), vec![8, 16]);
+        assert_eq!(automaton.grid().len_x, 5);
+        assert!(automaton.grid().iter().all(|&c| c));
+
+        let invert = |window: &[bool; 9]| !window[4];
+        automaton.step(invert);
+        assert_eq!(automaton.background(), Some(&true));
+        assert_eq!(automaton.grid().iter().filter(|&&c| c).count(), 7 * 7 - 5 * 5);
+    }
+}
@@ -1,13 +1,17 @@
 use std::{
     convert::{TryFrom, TryInto},
     error::Error,
-    fmt::Debug,
-    ops::Add,
+    fmt::{self, Debug},
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
     str::FromStr,
 };
 
+use super::utils::offset::SignedOffset;
+
 /// Helper struct for representing 2d values, i.e: coordinates, indexes, etc.
-#[derive(Copy, Clone, Debug)]
+///
+/// The ordering compares `x` first and then `y`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Base2d<U> {
     pub x: U,
     pub y: U,
@@ -35,6 +39,126 @@ impl<U: PartialEq> Base2d<U> {
     }
 }
 
+impl<U: Copy + Ord> Base2d<U> {
+    /// returns a Base2d with the smallest value of each component.
+    pub fn component_min(&self, rhs: &Self) -> Self {
+        Base2d::new(self.x.min(rhs.x), self.y.min(rhs.y))
+    }
+
+    /// returns a Base2d with the largest value of each component.
+    pub fn component_max(&self, rhs: &Self) -> Self {
+        Base2d::new(self.x.max(rhs.x), self.y.max(rhs.y))
+    }
+}
+
+//--------------------------------------------------------------------
+// Distances
+//--------------------------------------------------------------------
+// The differences between components are always taken as larger minus smaller, so the distances also work for
+// unsigned types.
+
+impl<U> Base2d<U>
+where
+    U: Copy + Ord + Sub<Output = U>,
+{
+    /// returns the absolute difference of each component.
+    pub fn abs_diff(&self, rhs: &Self) -> Self {
+        let diff = |a: U, b: U| if a > b { a - b } else { b - a };
+        Base2d::new(diff(self.x, rhs.x), diff(self.y, rhs.y))
+    }
+
+    /// returns the largest difference between components, i.e: the amount of moves between both points when diagonal
+    /// moves are allowed.
+    pub fn chebyshev(&self, rhs: &Self) -> U {
+        let d = self.abs_diff(rhs);
+        d.x.max(d.y)
+    }
+}
+
+impl<U> Base2d<U>
+where
+    U: Copy + Ord + Add<Output = U> + Sub<Output = U>,
+{
+    /// returns the sum of the differences between components, i.e: the amount of moves between both points when only
+    /// horizontal and vertical moves are allowed.
+    pub fn manhattan(&self, rhs: &Self) -> U {
+        let d = self.abs_diff(rhs);
+        d.x + d.y
+    }
+}
+
+impl<U> Base2d<U>
+where
+    U: Copy + Ord + Add<Output = U> + Sub<Output = U> + Mul<Output = U>,
+{
+    /// returns the square of the euclidean distance, which avoids dealing with non integer roots.
+    pub fn squared_euclidean(&self, rhs: &Self) -> U {
+        let d = self.abs_diff(rhs);
+        d.x * d.x + d.y * d.y
+    }
+}
+
+impl<U: Copy + Ord + From<i8>> Base2d<U> {
+    /// returns the sign of each component: `-1`, `0` or `1`. Turns a difference between two points into a single step
+    /// from one towards the other.
+    pub fn signum(&self) -> Self {
+        let sign = |v: U| {
+            let zero = U::from(0);
+            U::from(if v > zero {
+                1
+            } else if v < zero {
+                -1
+            } else {
+                0
+            })
+        };
+        Base2d::new(sign(self.x), sign(self.y))
+    }
+}
+
+//--------------------------------------------------------------------
+// Signed offsets
+//--------------------------------------------------------------------
+// Positions are usually unsigned, e.g. grid indexes, whereas the steps between them can go in any direction.
+
+impl<U: SignedOffset> Base2d<U> {
+    /// returns the position moved by `delta`, or `None` if any component leaves the range of the type.
+    pub fn checked_offset(&self, delta: &Base2d<U::Signed>) -> Option<Self> {
+        Some(Base2d {
+            x: self.x.checked_offset(delta.x)?,
+            y: self.y.checked_offset(delta.y)?,
+        })
+    }
+
+    /// returns the position moved by `delta`, wrapping each component around at the boundaries of the type.
+    pub fn wrapping_offset(&self, delta: &Base2d<U::Signed>) -> Self {
+        Base2d {
+            x: self.x.wrapping_offset(delta.x),
+            y: self.y.wrapping_offset(delta.y),
+        }
+    }
+
+    /// returns the position moved by `delta`, with each component clamped to the range of the type.
+    pub fn saturating_offset(&self, delta: &Base2d<U::Signed>) -> Self {
+        Base2d {
+            x: self.x.saturating_offset(delta.x),
+            y: self.y.saturating_offset(delta.y),
+        }
+    }
+
+    /// returns the offset which moves this position to `target`, or `None` if it does not fit the signed type.
+    pub fn checked_delta(&self, target: &Self) -> Option<Base2d<U::Signed>> {
+        Some(Base2d {
+            x: self.x.checked_delta(target.x)?,
+            y: self.y.checked_delta(target.y)?,
+        })
+    }
+}
+
+//--------------------------------------------------------------------
+// Arithmetic
+//--------------------------------------------------------------------
+
 impl<U> Add for Base2d<U>
 where
     U: Add<Output = U>,
@@ -49,6 +173,75 @@ where
     }
 }
 
+impl<U> Sub for Base2d<U>
+where
+    U: Sub<Output = U>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl<U> Neg for Base2d<U>
+where
+    U: Neg<Output = U>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self { x: -self.x, y: -self.y }
+    }
+}
+
+/// scalar multiplication
+impl<U> Mul<U> for Base2d<U>
+where
+    U: Mul<Output = U> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: U) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+/// scalar division
+impl<U> Div<U> for Base2d<U>
+where
+    U: Div<Output = U> + Copy,
+{
+    type Output = Self;
+
+    fn div(self, rhs: U) -> Self::Output {
+        Self {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl<U: AddAssign> AddAssign for Base2d<U> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<U: SubAssign> SubAssign for Base2d<U> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
 //--------------------------------------------------------------------
 // Conversion traits
 //--------------------------------------------------------------------
@@ -71,28 +264,99 @@ where
     }
 }
 
+//--------------------------------------------------------------------
+// Parsing
+//--------------------------------------------------------------------
+
+/// Component of a `Base2d`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+impl Axis {
+    fn label(self) -> char {
+        match self {
+            Axis::X => 'x',
+            Axis::Y => 'y',
+        }
+    }
+}
+
+/// Error returned when a string can not be parsed into a `Base2d`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBase2dError {
+    /// there is no value for the component.
+    Missing(Axis),
+    /// the value of the component is not valid. Holds the offending text and the reason given by its parser.
+    Invalid { axis: Axis, value: String, reason: String },
+    /// there is more input after both components.
+    TrailingInput(String),
+}
+
+impl fmt::Display for ParseBase2dError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBase2dError::Missing(axis) => write!(f, "missing value for {}", axis.label()),
+            ParseBase2dError::Invalid { axis, value, reason } => {
+                write!(f, "invalid value \"{}\" for {}: {}", value, axis.label(), reason)
+            }
+            ParseBase2dError::TrailingInput(rest) => write!(f, "unexpected input after both values: \"{}\"", rest),
+        }
+    }
+}
+
+impl Error for ParseBase2dError {}
+
 impl<U> FromStr for Base2d<U>
 where
     U: FromStr + Copy,
-    <U as FromStr>::Err: std::error::Error + 'static,
+    <U as FromStr>::Err: fmt::Display,
 {
-    type Err = Box<dyn Error>;
+    type Err = ParseBase2dError;
 
-    /// string needs to have two values separated by comma (','). Example: "15,21"
+    /// string needs to have two values separated by a comma or whitespace, optionally in parentheses or labeled.
+    /// Examples: "15,21", " 15, 21 ", "(15, 21)", "x=15, y=21", "15 21"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(',');
-        let x = iter
-            .next()
-            .ok_or("Could not parse the number before the comma.")?
-            .parse::<U>()?;
-        let y = iter
-            .next()
-            .ok_or("Could not parse the number after the comma.")?
-            .parse::<U>()?;
+        let s = s.trim();
+        let s = s
+            .strip_prefix('(')
+            .and_then(|inner| inner.strip_suffix(')'))
+            .unwrap_or(s);
+        let parts: Vec<_> = if s.contains(',') {
+            s.split(',').map(str::trim).collect()
+        } else {
+            s.split_whitespace().collect()
+        };
+        if parts.len() > 2 {
+            return Err(ParseBase2dError::TrailingInput(parts[2..].join(" ")));
+        }
+
+        let x = parse_component(parts.first().copied(), Axis::X)?;
+        let y = parse_component(parts.get(1).copied(), Axis::Y)?;
         Ok(Base2d::new(x, y))
     }
 }
 
+/// parses the value of a single component, which may be labeled, e.g. "x=15".
+fn parse_component<U>(part: Option<&str>, axis: Axis) -> Result<U, ParseBase2dError>
+where
+    U: FromStr,
+    <U as FromStr>::Err: fmt::Display,
+{
+    let part = part.filter(|p| !p.is_empty()).ok_or(ParseBase2dError::Missing(axis))?;
+    let value = match part.strip_prefix(axis.label()) {
+        Some(rest) => rest.trim_start().strip_prefix('=').map(str::trim_start).unwrap_or(part),
+        None => part,
+    };
+    value.parse::<U>().map_err(|e| ParseBase2dError::Invalid {
+        axis,
+        value: value.to_string(),
+        reason: e.to_string(),
+    })
+}
+
 // impl<U> From<(U, U)> for Base2d<usize>
 // where
 //     U: Into<usize>,
@@ -104,3 +368,78 @@ where
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let mut p = Base2d::new(3i32, -4);
+        let q = Base2d::new(-1, 2);
+        assert_eq!(p + q, Base2d::new(2, -2));
+        assert_eq!(p - q, Base2d::new(4, -6));
+        assert_eq!(-p, Base2d::new(-3, 4));
+        assert_eq!(p * 2, Base2d::new(6, -8));
+        assert_eq!(p / 2, Base2d::new(1, -2));
+        p += q;
+        assert_eq!(p, Base2d::new(2, -2));
+        p -= q * 2;
+        assert_eq!(p, Base2d::new(4, -6));
+        assert_eq!(p.signum(), Base2d::new(1, -1));
+        assert_eq!(Base2d::new(0i64, 7).signum(), Base2d::new(0, 1));
+        assert_eq!(p.component_min(&q), Base2d::new(-1, -6));
+        assert_eq!(p.component_max(&q), Base2d::new(4, 2));
+        assert!(Base2d::new(1, 9) < Base2d::new(2, 0));
+    }
+
+    #[test]
+    fn test_parsing() {
+        let expected = Ok(Base2d::new(15, -21));
+        assert_eq!("15,-21".parse::<Base2d<i32>>(), expected);
+        assert_eq!(" 15 , -21 ".parse::<Base2d<i32>>(), expected);
+        assert_eq!("(15, -21)".parse::<Base2d<i32>>(), expected);
+        assert_eq!("x=15, y=-21".parse::<Base2d<i32>>(), expected);
+        assert_eq!("x = 15, y = -21".parse::<Base2d<i32>>(), expected);
+        assert_eq!("x=15 y=-21".parse::<Base2d<i32>>(), expected);
+        assert_eq!("15\t-21".parse::<Base2d<i32>>(), expected);
+
+        assert_eq!("15".parse::<Base2d<i32>>(), Err(ParseBase2dError::Missing(Axis::Y)));
+        assert_eq!(",3".parse::<Base2d<i32>>(), Err(ParseBase2dError::Missing(Axis::X)));
+        assert_eq!("".parse::<Base2d<i32>>(), Err(ParseBase2dError::Missing(Axis::X)));
+        assert_eq!(
+            "1,2,3".parse::<Base2d<i32>>(),
+            Err(ParseBase2dError::TrailingInput("3".into()))
+        );
+        let err = "4, y=-2".parse::<Base2d<u8>>().unwrap_err();
+        assert!(matches!(&err, ParseBase2dError::Invalid { axis: Axis::Y, value, .. } if value == "-2"));
+        assert_eq!(
+            err.to_string(),
+            "invalid value \"-2\" for y: invalid digit found in string"
+        );
+        assert!(matches!(
+            "y=1, x=2".parse::<Base2d<u8>>(),
+            Err(ParseBase2dError::Invalid { axis: Axis::X, .. })
+        ));
+    }
+
+    #[test]
+    fn test_signed_offsets() {
+        let p = Base2d::new(2usize, 5);
+        assert_eq!(p.checked_offset(&Base2d::new(-2, 1)), Some(Base2d::new(0, 6)));
+        assert_eq!(p.checked_offset(&Base2d::new(-3, 1)), None);
+        assert_eq!(p.saturating_offset(&Base2d::new(-3, 1)), Base2d::new(0, 6));
+        assert_eq!(p.wrapping_offset(&Base2d::new(-3, 0)), Base2d::new(usize::MAX, 5));
+        assert_eq!(p.checked_delta(&Base2d::new(0, 9)), Some(Base2d::new(-2, 4)));
+    }
+
+    #[test]
+    fn test_distances() {
+        let (p, q) = (Base2d::new(1u16, 8), Base2d::new(4u16, 2));
+        assert_eq!(p.manhattan(&q), 9);
+        assert_eq!(q.manhattan(&p), 9);
+        assert_eq!(p.chebyshev(&q), 6);
+        assert_eq!(p.squared_euclidean(&q), 45);
+        assert_eq!(Base2d::new(-2i32, 0).manhattan(&Base2d::new(2, -3)), 7);
+    }
+}
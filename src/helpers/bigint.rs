@@ -0,0 +1,277 @@
+//! A minimal arbitrary-precision unsigned integer.
+//!
+//! Only supports what counting puzzles need: addition, multiplication, comparison and decimal conversion. The value is
+//! stored as base `2^32` digits (limbs), least significant first, without leading zeros, so zero has no limbs at all.
+
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul},
+    str::FromStr,
+};
+
+/// largest power of ten fitting a limb, used for decimal conversions.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>, // least significant first
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// returns the quotient and the remainder of the division by `divisor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_rem_u32(&self, divisor: u32) -> (BigUint, u32) {
+        assert_ne!(divisor, 0, "division by zero");
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut rem = 0u64;
+        for (q, &limb) in quotient.iter_mut().zip(&self.limbs).rev() {
+            let current = (rem << 32) | limb as u64;
+            *q = (current / divisor as u64) as u32;
+            rem = current % divisor as u64;
+        }
+        (BigUint::from_limbs(quotient), rem as u32)
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// creates a number removing the leading zeros.
+    fn from_limbs(mut limbs: Vec<u32>) -> BigUint {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    /// returns `self * factor + addend`, used for decimal parsing.
+    fn mul_add_u32(&self, factor: u32, addend: u32) -> BigUint {
+        let mut carry = addend as u64;
+        let mut limbs: Vec<_> = self
+            .limbs
+            .iter()
+            .map(|&limb| {
+                let v = limb as u64 * factor as u64 + carry;
+                carry = v >> 32;
+                v as u32
+            })
+            .collect();
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+//--------------------------------------------------------------------
+// Arithmetic
+//--------------------------------------------------------------------
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        if self.limbs.len() < rhs.limbs.len() {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+        let mut carry = 0u64;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            if carry == 0 && i >= rhs.limbs.len() {
+                break;
+            }
+            let v = *limb as u64 + *rhs.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            *limb = v as u32;
+            carry = v >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl AddAssign for BigUint {
+    fn add_assign(&mut self, rhs: BigUint) {
+        *self += &rhs;
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, rhs: BigUint) -> BigUint {
+        self += &rhs;
+        self
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let mut sum = self.clone();
+        sum += rhs;
+        sum
+    }
+}
+
+/// schoolbook multiplication, quadratic on the amount of limbs.
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                // a * b + limb + carry is at most 2^64 - 1, so it never overflows
+                let v = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = v as u32;
+                carry = v >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: BigUint) -> BigUint {
+        &self * &rhs
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> BigUint {
+        iter.fold(BigUint::zero(), |mut acc, v| {
+            acc += v;
+            acc
+        })
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> BigUint {
+        iter.fold(BigUint::zero(), |acc, v| acc + v)
+    }
+}
+
+//--------------------------------------------------------------------
+// Comparison
+//--------------------------------------------------------------------
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // without leading zeros, a longer number is always larger
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+//--------------------------------------------------------------------
+// Conversions
+//--------------------------------------------------------------------
+
+impl From<u64> for BigUint {
+    fn from(v: u64) -> Self {
+        BigUint::from_limbs(vec![v as u32, (v >> 32) as u32])
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // splits the number in chunks of 9 decimal digits, from the least significant one
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem_u32(DECIMAL_BASE);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+        let mut decimal = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            decimal.push_str(&format!("{:0width$}", chunk, width = DECIMAL_DIGITS));
+        }
+        f.pad_integral(true, "", &decimal)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = Box<dyn Error>;
+
+    /// string needs to be a non empty sequence of decimal digits. Example: "26984457539"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Invalid decimal number: \"{}\".", s).into());
+        }
+        // the first chunk takes the leftover digits so the following ones have exactly 9 digits
+        let first = match s.len() % DECIMAL_DIGITS {
+            0 => DECIMAL_DIGITS,
+            len => len,
+        };
+        let mut number = BigUint::from(s[..first].parse::<u64>()?);
+        for start in (first..s.len()).step_by(DECIMAL_DIGITS) {
+            let chunk = s[start..start + DECIMAL_DIGITS].parse()?;
+            number = number.mul_add_u32(DECIMAL_BASE, chunk);
+        }
+        Ok(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = BigUint::from(u64::MAX);
+        let b = BigUint::from(1);
+        assert_eq!((&a + &b).to_string(), "18446744073709551616");
+        assert_eq!((&a * &a).to_string(), "340282366920938463426481119284349108225");
+        assert_eq!(
+            [a.clone(), a.clone(), b.clone()].iter().sum::<BigUint>(),
+            &(&a * &BigUint::from(2)) + &b
+        );
+        assert_eq!(BigUint::zero() * a.clone(), BigUint::zero());
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(format!("{:>5}", BigUint::from(42)), "   42");
+
+        let (q, r) = BigUint::from(1_000_000_000_123).div_rem_u32(1000);
+        assert_eq!((q, r), (BigUint::from(1_000_000_000), 123));
+    }
+
+    #[test]
+    fn test_parsing_and_ordering() {
+        let factorial_30 = (1..=30).map(BigUint::from).fold(BigUint::from(1), |acc, v| acc * v);
+        let text = "265252859812191058636308480000000";
+        assert_eq!(factorial_30.to_string(), text);
+        assert_eq!(text.parse::<BigUint>().unwrap(), factorial_30);
+        assert_eq!("000000000000000000007".parse::<BigUint>().unwrap(), BigUint::from(7));
+        assert!("12a".parse::<BigUint>().is_err());
+        assert!("".parse::<BigUint>().is_err());
+
+        assert!(factorial_30 > BigUint::from(u64::MAX));
+        assert!(BigUint::from(1 << 40) > BigUint::from((1 << 40) - 1));
+        assert!(BigUint::zero() < BigUint::from(1));
+    }
+}
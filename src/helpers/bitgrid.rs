@@ -0,0 +1,176 @@
+//! A two-dimensional grid of booleans packed into `u64` words.
+//!
+//! Follows the same row major layout as `Grid`, but each row starts at a new word so bitwise operations between grids
+//! of the same dimensions are applied word by word. The unused bits at the end of each row are always kept cleared.
+
+use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign};
+
+use super::grid::Grid;
+
+const WORD_BITS: usize = 64;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    words_per_row: usize,
+    pub len_x: usize,
+    pub len_y: usize,
+}
+
+impl BitGrid {
+    /// creates a new grid with all the elements cleared.
+    pub fn new(len_x: usize, len_y: usize) -> BitGrid {
+        let words_per_row = len_x.div_ceil(WORD_BITS);
+        BitGrid {
+            words: vec![0; words_per_row * len_y],
+            words_per_row,
+            len_x,
+            len_y,
+        }
+    }
+
+    /// returns the value at position x,y.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (i, mask) = self.index(x, y);
+        self.words[i] & mask != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (i, mask) = self.index(x, y);
+        if value {
+            self.words[i] |= mask;
+        } else {
+            self.words[i] &= !mask;
+        }
+    }
+
+    /// flips the value at position x,y and returns the new value.
+    pub fn toggle(&mut self, x: usize, y: usize) -> bool {
+        let (i, mask) = self.index(x, y);
+        self.words[i] ^= mask;
+        self.words[i] & mask != 0
+    }
+
+    /// returns the amount of elements set to `true`.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// returns an iterator over the positions `(x, y)` set to `true`, in reading order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, x0) = (i / self.words_per_row, (i % self.words_per_row) * WORD_BITS);
+            (0..WORD_BITS)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| (x0 + bit, y))
+        })
+    }
+
+    /// returns a `Grid<bool>` with the same contents.
+    pub fn to_grid(&self) -> Grid<bool> {
+        let flat = (0..self.len_y)
+            .flat_map(|y| (0..self.len_x).map(move |x| (x, y)))
+            .map(|(x, y)| self.get(x, y))
+            .collect();
+        Grid::from_vec(self.len_x, self.len_y, flat)
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// returns the index of the word holding the element at x,y and the mask selecting its bit.
+    fn index(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(x < self.len_x && y < self.len_y, "index out of bounds");
+        (self.words_per_row * y + x / WORD_BITS, 1 << (x % WORD_BITS))
+    }
+
+    /// applies `op` to each pair of words of the grids.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grids do not have the same dimensions.
+    fn zip_words<F: Fn(&mut u64, u64)>(&mut self, rhs: &BitGrid, op: F) {
+        assert_eq!(
+            (self.len_x, self.len_y),
+            (rhs.len_x, rhs.len_y),
+            "grid dimensions differ"
+        );
+        self.words.iter_mut().zip(&rhs.words).for_each(|(w, &r)| op(w, r));
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut bits = BitGrid::new(grid.len_x, grid.len_y);
+        for y in 0..grid.len_y {
+            for (x, &v) in grid.line(y).iter().enumerate() {
+                bits.set(x, y, v);
+            }
+        }
+        bits
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        self.zip_words(rhs, |w, r| *w &= r);
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        self.zip_words(rhs, |w, r| *w |= r);
+    }
+}
+
+impl BitXorAssign<&BitGrid> for BitGrid {
+    fn bitxor_assign(&mut self, rhs: &BitGrid) {
+        self.zip_words(rhs, |w, r| *w ^= r);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits() {
+        let mut bits = BitGrid::new(70, 3);
+        bits.set(0, 0, true);
+        bits.set(69, 1, true);
+        assert!(bits.toggle(64, 2));
+        assert!(!bits.toggle(64, 2));
+        bits.set(64, 2, true);
+        assert!(bits.get(69, 1) && !bits.get(68, 1));
+        assert_eq!(bits.count_ones(), 3);
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), vec![(0, 0), (69, 1), (64, 2)]);
+
+        let grid = bits.to_grid();
+        assert!(*grid.get(69, 1));
+        assert_eq!(BitGrid::from(&grid), bits);
+    }
+
+    #[test]
+    fn test_bitwise() {
+        let mut a = BitGrid::new(3, 2);
+        let mut b = BitGrid::new(3, 2);
+        a.set(0, 0, true);
+        a.set(1, 1, true);
+        b.set(1, 1, true);
+        b.set(2, 1, true);
+
+        let mut and = a.clone();
+        and &= &b;
+        assert_eq!(and.iter_ones().collect::<Vec<_>>(), vec![(1, 1)]);
+        let mut or = a.clone();
+        or |= &b;
+        assert_eq!(or.count_ones(), 3);
+        a ^= &b;
+        assert_eq!(a.iter_ones().collect::<Vec<_>>(), vec![(0, 0), (2, 1)]);
+    }
+}
@@ -0,0 +1,206 @@
+//! Cycle detection for deterministic simulations.
+//!
+//! A simulation is modeled as the sequence `x0, f(x0), f(f(x0)), ...` for a step function `f`. When the state space is
+//! finite the sequence eventually loops: after `start` steps, the states repeat every `period` steps. Knowing both
+//! values allows jumping straight to any step `n`, no matter how large.
+//!
+//! - `floyd` and `brent` only keep a couple of states in memory, but require the sequence to loop (otherwise they never
+//!   return) and compute some steps more than once;
+//!
+//! - `find_cycle` and `fast_forward` store every state seen in a `HashMap`, computing each step only once. For `Grid`
+//!   states, `Grid::state_hash` may be used to derive a more compact key.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Describes where a sequence starts looping.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,  // index of the first state which is part of the loop
+    pub period: usize, // amount of steps until a state repeats
+}
+
+impl Cycle {
+    /// returns the smallest step `i` whose state is the same as the state at step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// returns the state at step `n` of the sequence starting at `x0`, computing at most `start + period` steps.
+    pub fn state_at<T, F>(&self, x0: &T, mut f: F, n: usize) -> T
+    where
+        T: Clone,
+        F: FnMut(&T) -> T,
+    {
+        (0..self.equivalent_step(n)).fold(x0.clone(), |x, _| f(&x))
+    }
+}
+
+//--------------------------------------------------------------------
+// Constant memory algorithms
+//--------------------------------------------------------------------
+
+/// Floyd's "tortoise and hare" algorithm.
+///
+/// # Warning
+///
+/// Never returns if the sequence does not loop.
+pub fn floyd<T, F>(x0: &T, mut f: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    // the hare moves twice as fast, they meet at a step multiple of the period
+    let mut tortoise = f(x0);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        let half = f(&hare);
+        hare = f(&half);
+    }
+
+    // moving both at the same speed, one from the start, they meet at the beginning of the loop
+    let mut start = 0;
+    tortoise = x0.clone();
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    let mut period = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        period += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Brent's algorithm, usually evaluates the step function less times than `floyd`.
+///
+/// # Warning
+///
+/// Never returns if the sequence does not loop.
+pub fn brent<T, F>(x0: &T, mut f: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    // searches successive powers of two until the hare meets the tortoise, the distance is the period
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = x0.clone();
+    let mut hare = f(x0);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = f(&hare);
+        period += 1;
+    }
+
+    // with the hare one period ahead, they meet at the beginning of the loop
+    let mut start = 0;
+    tortoise = x0.clone();
+    hare = (0..period).fold(x0.clone(), |x, _| f(&x));
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+//--------------------------------------------------------------------
+// Hash map algorithms
+//--------------------------------------------------------------------
+
+/// Finds a cycle by remembering every state seen. Returns `None` if no state repeats within `max_steps` steps.
+pub fn find_cycle<T, F>(x0: &T, mut f: F, max_steps: usize) -> Option<Cycle>
+where
+    T: Clone + Hash + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut seen = HashMap::new();
+    let mut x = x0.clone();
+    for step in 0..=max_steps {
+        if let Some(&start) = seen.get(&x) {
+            return Some(Cycle {
+                start,
+                period: step - start,
+            });
+        }
+        let next = f(&x);
+        seen.insert(x, step);
+        x = next;
+    }
+    None
+}
+
+/// Returns the state at step `n` of the sequence starting at `x0`. Stops simulating as soon as a state repeats and
+/// jumps directly to the equivalent state inside the loop.
+pub fn fast_forward<T, F>(x0: T, mut f: F, n: usize) -> T
+where
+    T: Clone + Hash + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut x = x0;
+    for step in 0..n {
+        if let Some(&start) = seen.get(&x) {
+            let cycle = Cycle {
+                start,
+                period: step - start,
+            };
+            return history.swap_remove(cycle.equivalent_step(n));
+        }
+        let next = f(&x);
+        seen.insert(x.clone(), step);
+        history.push(x);
+        x = next;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::grid::Grid;
+
+    #[test]
+    fn test_algorithms_agree() {
+        let f = |&x: &u64| (x * x + 1) % 255;
+        let cycle = find_cycle(&3, f, 1000).unwrap();
+        assert_eq!(floyd(&3, f), cycle);
+        assert_eq!(brent(&3, f), cycle);
+
+        let mut x = 3;
+        for n in 0..50 {
+            assert_eq!(cycle.state_at(&3, f, n), x);
+            assert_eq!(fast_forward(3, f, n), x);
+            x = f(&x);
+        }
+    }
+
+    #[test]
+    fn test_grid_states() {
+        // moves the first line to the bottom, the grid returns to its initial state every `len_y` steps
+        let rotate = |grid: &Grid<u8>| grid.map_with_pos(|x, y, _| *grid.get(x, (y + 1) % grid.len_y));
+        let grid = Grid::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+        let cycle = brent(&grid, rotate);
+        assert_eq!(cycle, Cycle { start: 0, period: 3 });
+
+        let far = fast_forward(grid.clone(), rotate, 1_000_000_000_000); // 10^12 % 3 == 1
+        assert_eq!(far, rotate(&grid));
+        assert_eq!(far.state_hash(), rotate(&grid).state_hash());
+        assert_ne!(far.state_hash(), grid.state_hash());
+    }
+}
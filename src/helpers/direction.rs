@@ -0,0 +1,257 @@
+//! Cardinal and 8-way directions for moving over a grid.
+//!
+//! Follows the orientation of `Grid`: `x` grows to the east (right) and `y` grows to the south (down), so `North` is
+//! the unit offset `(0, -1)`. Turning right is clockwise.
+
+use std::{convert::TryFrom, error::Error, fmt, str::FromStr};
+
+use super::base2d::Base2d;
+
+/// Error returned when a character or string does not name a direction.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseDirectionError(pub String);
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid direction: \"{}\"", self.0)
+    }
+}
+
+impl Error for ParseDirectionError {}
+
+//--------------------------------------------------------------------
+// Cardinal directions
+//--------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// all the directions, clockwise from `North`.
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    /// returns the direction after turning 90° counterclockwise.
+    pub fn turn_left(self) -> Direction {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    /// returns the direction after turning 90° clockwise.
+    pub fn turn_right(self) -> Direction {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// returns the unit offset of a step in this direction.
+    pub fn offset<U: From<i8>>(self) -> Base2d<U> {
+        let (x, y) = match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        };
+        Base2d {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+}
+
+/// Accepts `U/D/L/R`, `N/E/S/W` and the arrows `^/v/</>`.
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'N' | '^' => Ok(Direction::North),
+            'R' | 'E' | '>' => Ok(Direction::East),
+            'D' | 'S' | 'v' => Ok(Direction::South),
+            'L' | 'W' | '<' => Ok(Direction::West),
+            _ => Err(ParseDirectionError(c.to_string())),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            _ => Err(ParseDirectionError(s.to_string())),
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// 8-way directions
+//--------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// all the directions, clockwise from `North`.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    /// returns the direction after turning 45° counterclockwise.
+    pub fn turn_left(self) -> Direction8 {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// returns the direction after turning 45° clockwise.
+    pub fn turn_right(self) -> Direction8 {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub fn reverse(self) -> Direction8 {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// returns the offset of a step in this direction, diagonal ones move along both axes.
+    pub fn offset<U: From<i8>>(self) -> Base2d<U> {
+        let (x, y) = match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        };
+        Base2d {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Self {
+        Self::ALL[dir as usize * 2]
+    }
+}
+
+/// Accepts the same characters as `Direction`, as well as the compass points `NE/SE/SW/NW`.
+impl FromStr for Direction8 {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "NE" => Ok(Direction8::NorthEast),
+            "SE" => Ok(Direction8::SouthEast),
+            "SW" => Ok(Direction8::SouthWest),
+            "NW" => Ok(Direction8::NorthWest),
+            other => other.parse::<Direction>().map(Direction8::from),
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// Turtle
+//--------------------------------------------------------------------
+
+/// Tracks a position and the direction it is facing, e.g. for following movement instructions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Turtle {
+    pub pos: Base2d<i64>,
+    pub heading: Direction,
+}
+
+impl Turtle {
+    pub fn new(pos: Base2d<i64>, heading: Direction) -> Turtle {
+        Turtle { pos, heading }
+    }
+
+    /// moves `n` steps towards the heading.
+    pub fn forward(&mut self, n: i64) {
+        self.step(self.heading, n);
+    }
+
+    /// moves `n` steps towards `dir` without changing the heading.
+    pub fn step(&mut self, dir: Direction, n: i64) {
+        self.pos += dir.offset() * n;
+    }
+
+    pub fn turn_left(&mut self) {
+        self.heading = self.heading.turn_left();
+    }
+
+    pub fn turn_right(&mut self) {
+        self.heading = self.heading.turn_right();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotations() {
+        for &dir in Direction::ALL.iter() {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+            assert_eq!(dir.offset::<i32>() + dir.reverse().offset(), Base2d::new(0, 0));
+            assert_eq!(Direction8::from(dir).turn_right().turn_right(), dir.turn_right().into());
+        }
+        for &dir in Direction8::ALL.iter() {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.offset::<i32>() + dir.reverse().offset(), Base2d::new(0, 0));
+        }
+        assert_eq!(Direction::North.offset::<i64>(), Base2d::new(0, -1));
+        assert_eq!(Direction8::SouthWest.offset::<i64>(), Base2d::new(-1, 1));
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!(Direction::try_from('U'), Ok(Direction::North));
+        assert_eq!(Direction::try_from('>'), Ok(Direction::East));
+        assert_eq!(" S ".parse::<Direction>(), Ok(Direction::South));
+        assert_eq!("L".parse::<Direction>(), Ok(Direction::West));
+        assert!("X".parse::<Direction>().is_err());
+        assert!("UD".parse::<Direction>().is_err());
+        assert_eq!("NW".parse::<Direction8>(), Ok(Direction8::NorthWest));
+        assert_eq!("v".parse::<Direction8>(), Ok(Direction8::South));
+    }
+
+    #[test]
+    fn test_turtle() {
+        let mut turtle = Turtle::new(Base2d::new(0, 0), Direction::East);
+        turtle.forward(10);
+        turtle.turn_right();
+        turtle.forward(3);
+        turtle.turn_left();
+        turtle.turn_left();
+        turtle.step(Direction::West, 2);
+        turtle.forward(1);
+        assert_eq!(turtle.pos, Base2d::new(8, 2));
+        assert_eq!(turtle.heading, Direction::North);
+    }
+}
@@ -0,0 +1,350 @@
+//! Graphs whose nodes are named by strings, plus a union-find structure.
+//!
+//! `Graph` interns each node name into an `usize` id, given in order of first appearance, and stores the edges as
+//! adjacency lists indexed by id. The algorithms work with the ids, `name` converts them back into strings.
+//!
+//! `UnionFind` keeps track of disjoint sets of ids, e.g. to cluster nodes connected by some criteria.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    error::Error,
+    str::FromStr,
+};
+
+//--------------------------------------------------------------------
+// Graph
+//--------------------------------------------------------------------
+
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    adjacency: Vec<Vec<usize>>,
+    directed: bool,
+}
+
+impl Graph {
+    /// creates an empty graph where each edge can be followed both ways.
+    pub fn new_undirected() -> Graph {
+        Graph::default()
+    }
+
+    /// creates an empty graph where each edge only goes from its first node to the second one.
+    pub fn new_directed() -> Graph {
+        Graph {
+            directed: true,
+            ..Graph::default()
+        }
+    }
+
+    /// returns the id of the node `name`, adding it to the graph if needed.
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.adjacency.push(Vec::new());
+        id
+    }
+
+    /// adds an edge between the nodes `a` and `b`, adding the nodes if needed.
+    pub fn add_edge(&mut self, a: &str, b: &str) {
+        let (a, b) = (self.intern(a), self.intern(b));
+        self.adjacency[a].push(b);
+        if !self.directed {
+            self.adjacency[b].push(a);
+        }
+    }
+
+    //------------------------------
+    // Getters
+    //------------------------------
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there is no node with the given `id`.
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    /// returns the ids of the nodes reachable from `id` by a single edge.
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        &self.adjacency[id]
+    }
+
+    /// returns the amount of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    //------------------------------
+    // Traversals
+    //------------------------------
+
+    /// returns the nodes reachable from `start` in depth-first order, starting with `start` itself.
+    pub fn dfs(&self, start: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            if visited[id] {
+                continue;
+            }
+            visited[id] = true;
+            order.push(id);
+            // reversed so the neighbours are visited in the order they were added
+            stack.extend(self.adjacency[id].iter().rev().filter(|&&n| !visited[n]));
+        }
+        order
+    }
+
+    /// returns the nodes reachable from `start` in breadth-first order, together with the amount of edges from
+    /// `start`.
+    pub fn bfs(&self, start: usize) -> Vec<(usize, usize)> {
+        let mut visited = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from(vec![(start, 0)]);
+        visited[start] = true;
+        while let Some((id, dist)) = queue.pop_front() {
+            order.push((id, dist));
+            for &n in &self.adjacency[id] {
+                if !visited[n] {
+                    visited[n] = true;
+                    queue.push_back((n, dist + 1));
+                }
+            }
+        }
+        order
+    }
+
+    /// returns the nodes ordered so every edge goes from an earlier node to a later one, choosing the smallest id
+    /// available at each step. Returns `None` if the graph has a cycle, which includes any undirected edge.
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        let mut in_degree = vec![0; self.len()];
+        self.adjacency.iter().flatten().for_each(|&n| in_degree[n] += 1);
+
+        // Kahn's algorithm, a heap keeps the result deterministic
+        let mut ready: BinaryHeap<_> = (0..self.len()).filter(|&id| in_degree[id] == 0).map(Reverse).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(id)) = ready.pop() {
+            order.push(id);
+            for &n in &self.adjacency[id] {
+                in_degree[n] -= 1;
+                if in_degree[n] == 0 {
+                    ready.push(Reverse(n));
+                }
+            }
+        }
+        if order.len() == self.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    /// counts the paths from `start` to `end`. A path may only be extended to a node `n` if `can_visit(n, path)` is
+    /// `true`, where `path` holds the nodes visited so far, starting with `start`. Paths stop as soon as they reach
+    /// `end`.
+    ///
+    /// # Warning
+    ///
+    /// Never returns if `can_visit` allows infinitely long paths, e.g. by always returning `true` on a graph with
+    /// cycles.
+    pub fn count_paths<F>(&self, start: usize, end: usize, can_visit: F) -> usize
+    where
+        F: Fn(usize, &[usize]) -> bool,
+    {
+        let mut path = vec![start];
+        self.count_paths_from(&mut path, end, &can_visit)
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    fn count_paths_from<F>(&self, path: &mut Vec<usize>, end: usize, can_visit: &F) -> usize
+    where
+        F: Fn(usize, &[usize]) -> bool,
+    {
+        let last = *path.last().unwrap();
+        if last == end {
+            return 1;
+        }
+        let mut count = 0;
+        for &n in &self.adjacency[last] {
+            if can_visit(n, path) {
+                path.push(n);
+                count += self.count_paths_from(path, end, can_visit);
+                path.pop();
+            }
+        }
+        count
+    }
+}
+
+impl FromStr for Graph {
+    type Err = Box<dyn Error>;
+
+    /// creates an undirected graph, each line needs to hold an edge as two names separated by a dash ('-'). Example:
+    /// "start-A"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut graph = Graph::new_undirected();
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (a, b) = line
+                .split_once('-')
+                .ok_or_else(|| format!("Could not find the dash in the edge \"{}\".", line))?;
+            graph.add_edge(a.trim(), b.trim());
+        }
+        Ok(graph)
+    }
+}
+
+//--------------------------------------------------------------------
+// Union-Find
+//--------------------------------------------------------------------
+
+/// Disjoint sets of the ids `0..n`, each one initially in its own set. Joins sets by size and compresses the paths
+/// when looking for the representative of a set, so the operations take nearly constant time.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>, // only valid for the representatives
+    count: usize,     // amount of sets
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// returns the representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // path compression: every node on the way points directly to the root
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// joins the sets containing `a` and `b`. Returns `false` if they were already in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (small, large) = if self.size[a] < self.size[b] { (a, b) } else { (b, a) };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// returns the amount of elements in the set containing `x`.
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// returns the amount of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAVES: &str = "start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end";
+
+    /// small caves (lowercase) can not be visited twice, except for a single one if `repeat` is `true`.
+    fn count_cave_paths(graph: &Graph, repeat: bool) -> usize {
+        let (start, end) = (graph.id("start").unwrap(), graph.id("end").unwrap());
+        graph.count_paths(start, end, |n, path| {
+            if n == start {
+                return false;
+            }
+            if graph.name(n).chars().all(char::is_uppercase) || !path.contains(&n) {
+                return true;
+            }
+            let small: Vec<_> = path
+                .iter()
+                .filter(|&&p| graph.name(p).chars().all(char::is_lowercase))
+                .collect();
+            let repeated = (1..small.len()).any(|i| small[i..].contains(&small[i - 1]));
+            repeat && !repeated
+        })
+    }
+
+    #[test]
+    fn test_graph_paths() {
+        let graph: Graph = CAVES.parse().unwrap();
+        assert_eq!(graph.len(), 6);
+        assert_eq!(count_cave_paths(&graph, false), 10);
+        assert_eq!(count_cave_paths(&graph, true), 36);
+        assert!("start-A\nstartA".parse::<Graph>().is_err());
+    }
+
+    #[test]
+    fn test_traversals() {
+        let graph: Graph = CAVES.parse().unwrap();
+        let names = |ids: Vec<usize>| ids.iter().map(|&id| graph.name(id).to_string()).collect::<Vec<_>>();
+        let start = graph.id("start").unwrap();
+        assert_eq!(names(graph.dfs(start)), vec!["start", "A", "c", "b", "d", "end"]);
+        let bfs = graph.bfs(start);
+        assert_eq!(
+            names(bfs.iter().map(|&(id, _)| id).collect()),
+            vec!["start", "A", "b", "c", "end", "d"]
+        );
+        assert_eq!(bfs.iter().map(|&(_, d)| d).collect::<Vec<_>>(), vec![0, 1, 1, 2, 2, 2]);
+        assert_eq!(graph.topological_sort(), None);
+
+        let mut tasks = Graph::new_directed();
+        for edge in "C-A C-F A-B A-D B-E D-E F-E".split_whitespace() {
+            let (a, b) = edge.split_once('-').unwrap();
+            tasks.add_edge(a, b);
+        }
+        let order = tasks.topological_sort().unwrap();
+        assert_eq!(order.iter().map(|&id| tasks.name(id)).collect::<String>(), "CAFBDE");
+        tasks.add_edge("E", "C");
+        assert_eq!(tasks.topological_sort(), None);
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3) && !sets.connected(0, 4));
+        assert_eq!((sets.set_size(2), sets.set_size(5)), (4, 1));
+        assert_eq!(sets.count(), 3);
+    }
+}
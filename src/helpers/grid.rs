@@ -34,13 +34,65 @@
 //!
 //! Panics if the indexing inside square brackets is done with negative values.
 
-use std::ops::AddAssign;
+use std::{
+    collections::{hash_map::DefaultHasher, VecDeque},
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::AddAssign,
+};
+
+use super::rect::Rect;
 
 // use std::{convert::TryInto, fmt::Debug, ops::Index};
 
 // use super::base2d::Base2d;
 
+/// Defines which cells are considered adjacent to a given cell.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,  // orthogonal neighbours only
+    Eight, // orthogonal and diagonal neighbours
+}
+
+impl Connectivity {
+    /// returns the `(dx, dy)` offsets from a cell to its neighbours, in reading order.
+    pub fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+        }
+    }
+}
+
+/// Errors returned by the validating constructors of `Grid`.
 #[derive(Debug, PartialEq, Eq, Clone)]
+pub enum GridError {
+    /// the amount of elements is not `len_x * len_y`.
+    LengthMismatch { expected: usize, found: usize },
+    /// the amount of elements is not a multiple of the line length, so the last line would be incomplete.
+    IncompleteLine { len_x: usize, remainder: usize },
+    /// lines must have at least one element.
+    ZeroWidth,
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::LengthMismatch { expected, found } => {
+                write!(f, "expected {} elements for the grid, found {}", expected, found)
+            }
+            GridError::IncompleteLine { len_x, remainder } => {
+                write!(f, "last line has {} elements, expected {}", remainder, len_x)
+            }
+            GridError::ZeroWidth => write!(f, "grid lines must have at least one element"),
+        }
+    }
+}
+
+impl Error for GridError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct Grid<T> {
     flat: Vec<T>,
     pub len_x: usize,
@@ -73,7 +125,7 @@ impl<T: Clone> Grid<T> {
     // }
 
     // if the iterator does not have enough items to complete the last line, the
-    // remanining elements will be completed with the border value.
+    // remanining elements will be completed with the border value. Use `try_new_bordered` for validating the input.
     pub fn new_bordered_with_x<I>(len_x: usize, border: T, iter: I) -> Grid<T>
     where
         I: IntoIterator<Item = T>,
@@ -93,8 +145,44 @@ impl<T: Clone> Grid<T> {
         Grid::from_vec(len, flat.len() / len, flat)
     }
 
+    /// Creates a grid surrounded by a border `border_width` elements wide on every side, filled with the `border`
+    /// value. The inner elements are read from `iter` in row major order, each line having `len_x` elements.
+    ///
+    /// Returns an error if `len_x` is zero or if the amount of elements is not a multiple of `len_x`.
+    pub fn try_new_bordered<I>(len_x: usize, border_width: usize, border: T, iter: I) -> Result<Grid<T>, GridError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        if len_x == 0 {
+            return Err(GridError::ZeroWidth);
+        }
+        let inner: Vec<T> = iter.into_iter().collect();
+        let remainder = inner.len() % len_x;
+        if remainder != 0 {
+            return Err(GridError::IncompleteLine { len_x, remainder });
+        }
+
+        let grid = Grid::try_from_vec(len_x, inner.len() / len_x, inner)?;
+        let width = (border_width, border_width);
+        Ok(grid.padded(width, width, border))
+    }
+
+    /// Creates a grid from a vector in row major order.
+    ///
+    /// Returns an error if the length of `v` is not `len_x` * `len_y`.
+    pub fn try_from_vec(len_x: usize, len_y: usize, v: Vec<T>) -> Result<Grid<T>, GridError> {
+        if v.len() != len_x * len_y {
+            return Err(GridError::LengthMismatch {
+                expected: len_x * len_y,
+                found: v.len(),
+            });
+        }
+
+        Ok(Grid { flat: v, len_x, len_y })
+    }
+
     /// If vector `v` is larger than `len_x` * `len_y`, the extra elements are
-    /// truncated.
+    /// truncated. Use `try_from_vec` for validating the input.
     ///
     /// # Safety
     ///
@@ -113,8 +201,62 @@ impl<T: Clone> Grid<T> {
         }
     }
 
-    // pub fn extend_with_x()
-    // pub fn extend_with_y()
+    /// appends `n` columns filled with `value` to the right side of the grid.
+    pub fn extend_with_x(&mut self, n: usize, value: T) {
+        *self = self.padded((0, 0), (n, 0), value);
+    }
+
+    /// appends `n` rows filled with `value` to the bottom of the grid.
+    pub fn extend_with_y(&mut self, n: usize, value: T) {
+        self.len_y += n;
+        self.flat.resize(self.len_x * self.len_y, value);
+    }
+
+    /// returns a copy of the grid surrounded by cells filled with `value`. `before` is the amount of columns and rows
+    /// `(x, y)` added to the left and top sides, whereas `after` is the amount added to the right and bottom sides.
+    ///
+    /// The element at `x, y` in the original grid is moved to `x + before.0, y + before.1`.
+    pub fn padded(&self, before: (usize, usize), after: (usize, usize), value: T) -> Grid<T> {
+        let len_x = before.0 + self.len_x + after.0;
+        let len_y = before.1 + self.len_y + after.1;
+        let mut flat = Vec::with_capacity(len_x * len_y);
+
+        flat.resize(len_x * before.1, value.clone()); // upper rows
+        for y in 0..self.len_y {
+            flat.extend(std::iter::repeat_n(value.clone(), before.0));
+            flat.extend_from_slice(&self.flat[self.index(0, y)..self.index(0, y + 1)]);
+            flat.extend(std::iter::repeat_n(value.clone(), after.0));
+        }
+        flat.resize(len_x * len_y, value); // lower rows
+
+        Grid { flat, len_x, len_y }
+    }
+
+    /// sets every element inside the `rect`angle to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle is not inside the grid.
+    pub fn fill_rect(&mut self, rect: &Rect<usize>, value: T) {
+        assert!(rect.max.x < self.len_x && rect.max.y < self.len_y);
+        for y in rect.min.y..=rect.max.y {
+            let (i0, i1) = (self.index(rect.min.x, y), self.index(rect.max.x, y));
+            self.flat[i0..=i1].fill(value.clone());
+        }
+    }
+
+    /// returns a new grid with a copy of the elements inside the `rect`angle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle is not inside the grid.
+    pub fn sub_grid(&self, rect: &Rect<usize>) -> Grid<T> {
+        Grid {
+            flat: self.rect_iter(rect).cloned().collect(),
+            len_x: rect.width(),
+            len_y: rect.height(),
+        }
+    }
 }
 
 impl<T> Grid<T> {
@@ -231,6 +373,17 @@ impl<T> Grid<T> {
     //     iter
     // }
 
+    /// returns an iterator over the elements inside the `rect`angle, in reading order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle is not inside the grid.
+    pub fn rect_iter(&self, rect: &Rect<usize>) -> impl Iterator<Item = &T> {
+        let (min, max) = (rect.min, rect.max);
+        assert!(max.x < self.len_x && max.y < self.len_y);
+        (min.y..=max.y).flat_map(move |y| &self.flat[self.index(min.x, y)..=self.index(max.x, y)])
+    }
+
     // returns an array slice for a line of the grid
     pub fn line(&self, y: usize) -> &[T] {
         &self.flat[self.index(0, y)..=self.index(self.len_x - 1, y)]
@@ -241,23 +394,222 @@ impl<T> Grid<T> {
         &self.flat[self.index(border_size, y)..=self.index(self.len_x - border_size - 1, y)]
     }
 
+    /// returns an iterator over the positions `(x, y)` adjacent to `x, y` which are inside the grid.
+    pub fn neighbours(&self, x: usize, y: usize, connectivity: Connectivity) -> impl Iterator<Item = (usize, usize)> {
+        let (len_x, len_y) = (self.len_x, self.len_y);
+        connectivity.offsets().iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx).filter(|&nx| nx < len_x)?;
+            let ny = y.checked_add_signed(dy).filter(|&ny| ny < len_y)?;
+            Some((nx, ny))
+        })
+    }
+
+    //------------------------------
+    // Combinators
+    //------------------------------
+
+    /// returns a new grid with the same dimensions where each element is `f` applied to the element at the same
+    /// position.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            flat: self.flat.iter().map(f).collect(),
+            len_x: self.len_x,
+            len_y: self.len_y,
+        }
+    }
+
+    /// same as `map`, but `f` also receives the position `x, y` of the element.
+    pub fn map_with_pos<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(usize, usize, &T) -> U,
+    {
+        let len_x = self.len_x;
+        let flat = self.flat.iter().enumerate().map(|(i, v)| f(i % len_x, i / len_x, v));
+        Grid {
+            flat: flat.collect(),
+            len_x: self.len_x,
+            len_y: self.len_y,
+        }
+    }
+
+    /// returns a new grid where each element is `f` applied to the elements at the same position in both grids.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grids do not have the same dimensions.
+    pub fn zip_with<U, V, F>(&self, other: &Grid<U>, mut f: F) -> Grid<V>
+    where
+        F: FnMut(&T, &U) -> V,
+    {
+        assert_eq!((self.len_x, self.len_y), (other.len_x, other.len_y));
+        Grid {
+            flat: self.flat.iter().zip(&other.flat).map(|(a, b)| f(a, b)).collect(),
+            len_x: self.len_x,
+            len_y: self.len_y,
+        }
+    }
+
+    /// folds each row of the grid, from left to right, returning one accumulated value per row.
+    pub fn fold_rows<A, F>(&self, init: A, mut f: F) -> Vec<A>
+    where
+        A: Clone,
+        F: FnMut(A, &T) -> A,
+    {
+        (0..self.len_y)
+            .map(|y| self.line(y).iter().fold(init.clone(), &mut f))
+            .collect()
+    }
+
+    /// folds each column of the grid, from top to bottom, returning one accumulated value per column.
+    pub fn fold_columns<A, F>(&self, init: A, mut f: F) -> Vec<A>
+    where
+        A: Clone,
+        F: FnMut(A, &T) -> A,
+    {
+        (0..self.len_x)
+            .map(|x| (0..self.len_y).fold(init.clone(), |acc, y| f(acc, self.get(x, y))))
+            .collect()
+    }
+
+    /// returns the amount of elements for which `predicate` returns `true`.
+    pub fn count_where<F>(&self, mut predicate: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.flat.iter().filter(|v| predicate(v)).count()
+    }
+
+    //------------------------------
+    // Regions
+    //------------------------------
+
+    /// returns the positions of the region containing the seed `x, y`, where a region is a set of connected cells for
+    /// which `passable` returns `true`. The seed is the first position returned.
+    ///
+    /// Returns an empty `Vec` if the seed itself is not passable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the seed is out of bounds.
+    pub fn flood_fill<F>(&self, x: usize, y: usize, connectivity: Connectivity, passable: F) -> Vec<(usize, usize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut visited = vec![false; self.size()];
+        self.fill_from(x, y, connectivity, &passable, &mut visited)
+    }
+
+    /// labels every connected region of passable cells. Returns a grid holding the label of the region each cell
+    /// belongs to (`None` for cells which are not passable) and a `Vec` with the size of each region, indexed by
+    /// label.
+    ///
+    /// Labels are given in reading order of the first cell of each region, starting at 0.
+    pub fn label_components<F>(&self, connectivity: Connectivity, passable: F) -> (Grid<Option<usize>>, Vec<usize>)
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut labels = Grid::new(self.len_x, self.len_y, None);
+        let mut sizes = Vec::new();
+        let mut visited = vec![false; self.size()];
+
+        for y in 0..self.len_y {
+            for x in 0..self.len_x {
+                if visited[self.index(x, y)] {
+                    continue;
+                }
+                let region = self.fill_from(x, y, connectivity, &passable, &mut visited);
+                if region.is_empty() {
+                    continue;
+                }
+                for &(rx, ry) in &region {
+                    labels.update_at(rx, ry, Some(sizes.len()));
+                }
+                sizes.push(region.len());
+            }
+        }
+
+        (labels, sizes)
+    }
+
     //------------------------------
     // Helpers
     //------------------------------
 
+    /// translates the position `x, y` of the inner grid into the position in this grid, surrounded by a border
+    /// `border_width` elements wide. Returns `None` if the position is outside of the inner grid.
+    pub fn inner_to_bordered(&self, x: usize, y: usize, border_width: usize) -> Option<(usize, usize)> {
+        let bordered = |v: usize, len: usize| (v + 2 * border_width < len).then(|| v + border_width);
+        Some((bordered(x, self.len_x)?, bordered(y, self.len_y)?))
+    }
+
+    /// translates the position `x, y` of this grid, surrounded by a border `border_width` elements wide, into the
+    /// position in the inner grid. Returns `None` if the position is on the border or outside of the grid.
+    pub fn bordered_to_inner(&self, x: usize, y: usize, border_width: usize) -> Option<(usize, usize)> {
+        let inner = |v: usize, len: usize| v.checked_sub(border_width).filter(|&i| i + 2 * border_width < len);
+        Some((inner(x, self.len_x)?, inner(y, self.len_y)?))
+    }
+
     /// returns the total size of the array (len_x * len_y)
     pub fn size(&self) -> usize {
         self.flat.len()
     }
 
+    /// returns the index for acessing the `flat` array from the coordinates `x`
+    /// and `y`. Also valid for any other flat array with the same layout as the grid.
+    pub(crate) fn index(&self, x: usize, y: usize) -> usize {
+        self.len_x * y + x
+    }
+
     //------------------------------
     // Private
     //------------------------------
 
-    /// returns the index for acessing the `flat` array from the coordinates `x`
-    /// and `y`.
-    fn index(&self, x: usize, y: usize) -> usize {
-        self.len_x * y + x
+    /// breadth first search from `x, y` over passable cells not yet `visited`, marking the ones it reaches.
+    fn fill_from<F>(
+        &self,
+        x: usize,
+        y: usize,
+        connectivity: Connectivity,
+        passable: &F,
+        visited: &mut [bool],
+    ) -> Vec<(usize, usize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut region = Vec::new();
+        let seed = self.index(x, y);
+        if visited[seed] || !passable(&self.flat[seed]) {
+            return region;
+        }
+
+        visited[seed] = true;
+        let mut queue = VecDeque::from(vec![(x, y)]);
+        while let Some((cx, cy)) = queue.pop_front() {
+            region.push((cx, cy));
+            for (nx, ny) in self.neighbours(cx, cy, connectivity) {
+                let i = self.index(nx, ny);
+                if !visited[i] && passable(&self.flat[i]) {
+                    visited[i] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        region
+    }
+}
+
+impl<T: Hash> Grid<T> {
+    /// returns a hash of the dimensions and contents of the grid, a compact key for remembering visited states.
+    ///
+    /// Different grids may have the same hash, although it is unlikely.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
     }
 }
 
@@ -268,6 +620,46 @@ impl<T: AddAssign> Grid<T> {
     }
 }
 
+//--------------------------------------------------------------------
+// Grid2d
+//--------------------------------------------------------------------
+
+/// Common interface for two-dimensional storages indexed by `x, y`, allowing the same solution to run either over a
+/// dense `Grid` or over a `SparseGrid`.
+pub trait Grid2d<T> {
+    /// returns the value at position x,y.
+    fn get(&self, x: usize, y: usize) -> &T;
+
+    /// returns a mutable reference to the value at position x,y.
+    fn get_mut(&mut self, x: usize, y: usize) -> &mut T;
+
+    /// returns an iterator over the stored values. Sparse storages only yield the cells which have been written.
+    fn values<'a>(&'a self) -> Box<dyn Iterator<Item = &'a T> + 'a>;
+
+    /// returns the amount of stored values for which `predicate` returns `true`.
+    fn count_where<F>(&self, mut predicate: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+        Self: Sized,
+    {
+        self.values().filter(|v| predicate(v)).count()
+    }
+}
+
+impl<T> Grid2d<T> for Grid<T> {
+    fn get(&self, x: usize, y: usize) -> &T {
+        Grid::get(self, x, y)
+    }
+
+    fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        Grid::get_mut(self, x, y)
+    }
+
+    fn values<'a>(&'a self) -> Box<dyn Iterator<Item = &'a T> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
 // impl<T, V> Index<V> for Grid<T>
 // where
 //     V: TryInto<Base2d<usize>>,
@@ -291,3 +683,129 @@ impl<T: AddAssign> Grid<T> {
 //         let _ = grid[(3, 3)];
 //     }
 // }
+
+#[cfg(test)]
+impl<T: Clone> Grid<T> {
+    /// builds a grid from lines of text of the same length, converting each character with `f`. Test fixture shared by
+    /// the helpers working on grids.
+    pub(crate) fn from_lines<F: Fn(char) -> T>(s: &str, f: F) -> Grid<T> {
+        let len_x = s.lines().next().unwrap().len();
+        let flat: Vec<_> = s.lines().flat_map(|l| l.chars().map(&f)).collect();
+        Grid::from_vec(len_x, flat.len() / len_x, flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// builds a grid from lines of text where `#` is a wall and any other character is open.
+    fn grid_from(s: &str) -> Grid<bool> {
+        Grid::from_lines(s, |c| c != '#')
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = grid_from(".#..\n.#..\n#...\n");
+        assert_eq!(grid.flood_fill(0, 0, Connectivity::Four, |&v| v), vec![(0, 0), (0, 1)]);
+        assert_eq!(grid.flood_fill(2, 0, Connectivity::Four, |&v| v).len(), 7);
+        assert_eq!(grid.flood_fill(0, 0, Connectivity::Eight, |&v| v).len(), 9);
+        assert!(grid.flood_fill(1, 0, Connectivity::Four, |&v| v).is_empty());
+    }
+
+    #[test]
+    fn test_label_components() {
+        let grid = grid_from("..#.\n#.#.\n##..\n.#.#\n");
+        let (labels, sizes) = grid.label_components(Connectivity::Four, |&v| v);
+        assert_eq!(sizes, vec![3, 5, 1]);
+        assert_eq!(*labels.get(1, 1), Some(0));
+        assert_eq!(*labels.get(2, 3), Some(1));
+        assert_eq!(*labels.get(0, 3), Some(2));
+        assert_eq!(*labels.get(2, 0), None);
+
+        let (_, sizes) = grid.label_components(Connectivity::Eight, |&v| v);
+        assert_eq!(sizes, vec![8, 1]);
+    }
+
+    #[test]
+    fn test_rect_operations() {
+        use crate::helpers::base2d::Base2d;
+
+        let mut grid = Grid::from_vec(4, 3, (0u8..12).collect());
+        let rect = Rect::new(Base2d::new(2, 2), Base2d::new(1, 1));
+        assert_eq!(grid.rect_iter(&rect).copied().collect::<Vec<_>>(), vec![5, 6, 9, 10]);
+
+        let sub = grid.sub_grid(&rect);
+        assert_eq!((sub.len_x, sub.len_y), (2, 2));
+        assert_eq!(sub.line(1), &[9, 10]);
+
+        grid.fill_rect(&rect, 0);
+        assert_eq!(grid.line(1), &[4, 0, 0, 7]);
+        assert_eq!(grid.iter().map(|&v| v as u32).sum::<u32>(), 66 - 30);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fill_rect_out_of_bounds() {
+        use crate::helpers::base2d::Base2d;
+
+        let mut grid = Grid::new(4, 3, 0u8);
+        grid.fill_rect(&Rect::new(Base2d::new(3, 0), Base2d::new(4, 0)), 9);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let grid = Grid::from_vec(3, 2, vec![1u32, 2, 3, 4, 5, 6]);
+        let doubled = grid.map(|&v| v * 2);
+        assert_eq!(doubled.line(1), &[8, 10, 12]);
+
+        let positions = grid.map_with_pos(|x, y, _| (x, y));
+        assert_eq!(*positions.get(2, 1), (2, 1));
+
+        let diff = doubled.zip_with(&grid, |a, b| a - b);
+        assert_eq!(diff, grid);
+
+        assert_eq!(grid.fold_rows(0, |acc, &v| acc + v), vec![6, 15]);
+        assert_eq!(grid.fold_columns(1, |acc, &v| acc * v), vec![4, 10, 18]);
+        assert_eq!(grid.count_where(|&v| v % 2 == 0), 3);
+    }
+
+    #[test]
+    fn test_validated_constructors() {
+        assert!(Grid::try_from_vec(2, 3, vec![0u8; 6]).is_ok());
+        assert_eq!(
+            Grid::try_from_vec(2, 3, vec![0u8; 7]),
+            Err(GridError::LengthMismatch { expected: 6, found: 7 })
+        );
+        assert_eq!(
+            Grid::try_new_bordered(3, 1, 0u8, vec![1; 7]),
+            Err(GridError::IncompleteLine { len_x: 3, remainder: 1 })
+        );
+        assert_eq!(Grid::try_new_bordered(0, 1, 0u8, vec![]), Err(GridError::ZeroWidth));
+
+        let grid = Grid::try_new_bordered(3, 2, 0u8, 1..=6).unwrap();
+        assert_eq!((grid.len_x, grid.len_y), (7, 6));
+        assert_eq!(grid.line(3), &[0, 0, 4, 5, 6, 0, 0]);
+        assert_eq!(grid.inner_to_bordered(1, 1, 2), Some((3, 3)));
+        assert_eq!(grid.inner_to_bordered(2, 1, 2), Some((4, 3)));
+        assert_eq!(grid.inner_to_bordered(3, 1, 2), None);
+        assert_eq!(grid.inner_to_bordered(0, 2, 2), None);
+        assert_eq!(*grid.get(3, 3), 5);
+        assert_eq!(grid.bordered_to_inner(4, 3, 2), Some((2, 1)));
+        assert_eq!(grid.bordered_to_inner(5, 3, 2), None);
+        assert_eq!(grid.bordered_to_inner(1, 3, 2), None);
+    }
+
+    #[test]
+    fn test_padded() {
+        let mut grid = Grid::new(2, 1, 1u8);
+        grid.extend_with_x(1, 2);
+        grid.extend_with_y(1, 3);
+        assert_eq!(grid.iter().copied().collect::<Vec<_>>(), vec![1, 1, 2, 3, 3, 3]);
+
+        let padded = grid.padded((1, 0), (0, 1), 0);
+        assert_eq!((padded.len_x, padded.len_y), (4, 3));
+        assert_eq!(padded.line(0), &[0, 1, 1, 2]);
+        assert_eq!(padded.line(2), &[0, 0, 0, 0]);
+    }
+}
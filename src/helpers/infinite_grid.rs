@@ -0,0 +1,246 @@
+//! A two-dimensional grid without fixed dimensions.
+//!
+//! Internally stores a dense `Grid` together with an `origin`, the coordinates of the cell kept at the position
+//! `(0, 0)` of the inner grid, so positions may be negative. Writing outside the stored area grows the inner grid on
+//! the needed sides, whereas reading outside of it yields the `background` value. The cells never written hold the
+//! background value even inside of the stored area, also after changing it, so the contents of the grid do not depend
+//! on how it has grown. Equality and hashing follow the same rule.
+//!
+//! Uses `i64` coordinates following the same convention as `Grid`: `x` is the column and `y` the row.
+
+use std::{
+    hash::{Hash, Hasher},
+    ops::Range,
+};
+
+use super::{base2d::Base2d, grid::Grid};
+
+#[derive(Debug, Clone)]
+pub struct InfiniteGrid<T> {
+    grid: Grid<T>,
+    written: Grid<bool>, // same layout as `grid`, marks the cells which have been written
+    origin: Base2d<i64>, // coordinates of the cell at position (0, 0) of the inner grid
+    background: T,
+}
+
+impl<T: Clone> InfiniteGrid<T> {
+    /// creates an empty grid where every cell holds the `background` value.
+    pub fn new(background: T) -> InfiniteGrid<T> {
+        InfiniteGrid {
+            grid: Grid::new(0, 0, background.clone()),
+            written: Grid::new(0, 0, false),
+            origin: Base2d::new(0, 0),
+            background,
+        }
+    }
+
+    /// creates a grid from a dense `grid`, its element at `(0, 0)` is placed at the coordinates `origin`. Every
+    /// element of `grid` counts as written.
+    pub fn from_grid(grid: Grid<T>, origin: Base2d<i64>, background: T) -> InfiniteGrid<T> {
+        InfiniteGrid {
+            written: Grid::new(grid.len_x, grid.len_y, true),
+            grid,
+            origin,
+            background,
+        }
+    }
+
+    /// returns a mutable reference to the value at position x,y, growing the grid if the position is outside of the
+    /// stored area.
+    pub fn get_mut(&mut self, x: i64, y: i64) -> &mut T {
+        self.grow_to_include(x, y);
+        let (ix, iy) = self.inner_position(x, y).unwrap();
+        self.written.update_at(ix, iy, true);
+        self.grid.get_mut(ix, iy)
+    }
+
+    /// sets the value at position x,y, growing the grid if the position is outside of the stored area.
+    pub fn update_at(&mut self, x: i64, y: i64, value: T) {
+        *self.get_mut(x, y) = value;
+    }
+
+    /// grows the stored area, if needed, so it includes the position x,y.
+    ///
+    /// Each side grows at least by its current length, so repeated writes just outside of the borders are amortized.
+    pub fn grow_to_include(&mut self, x: i64, y: i64) {
+        let (x_range, y_range) = (self.x_range(), self.y_range());
+        if self.grid.size() == 0 {
+            self.grid = Grid::new(1, 1, self.background.clone());
+            self.written = Grid::new(1, 1, false);
+            self.origin = Base2d::new(x, y);
+            return;
+        }
+        if x_range.contains(&x) && y_range.contains(&y) {
+            return;
+        }
+
+        let (len_x, len_y) = (self.grid.len_x, self.grid.len_y);
+        let grow = |missing: i64, len: usize| if missing > 0 { len.max(missing as usize) } else { 0 };
+        let left = grow(x_range.start - x, len_x);
+        let right = grow(x - x_range.end + 1, len_x);
+        let top = grow(y_range.start - y, len_y);
+        let bottom = grow(y - y_range.end + 1, len_y);
+
+        self.grow((left, top), (right, bottom));
+    }
+
+    /// grows the stored area by `n` cells on every side.
+    pub fn pad(&mut self, n: usize) {
+        self.grow((n, n), (n, n));
+    }
+
+    /// changes the value of the cells never written, both inside and outside of the stored area.
+    pub fn set_background(&mut self, background: T) {
+        for y in 0..self.grid.len_y {
+            for x in 0..self.grid.len_x {
+                if !*self.written.get(x, y) {
+                    self.grid.update_at(x, y, background.clone());
+                }
+            }
+        }
+        self.background = background;
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// adds `before` columns and rows on the left and top sides and `after` ones on the right and bottom sides.
+    fn grow(&mut self, before: (usize, usize), after: (usize, usize)) {
+        self.grid = self.grid.padded(before, after, self.background.clone());
+        self.written = self.written.padded(before, after, false);
+        self.origin = Base2d::new(self.origin.x - before.0 as i64, self.origin.y - before.1 as i64);
+    }
+}
+
+impl<T> InfiniteGrid<T> {
+    /// returns the value at position x,y, or the background value if the position is outside of the stored area.
+    pub fn get(&self, x: i64, y: i64) -> &T {
+        match self.inner_position(x, y) {
+            Some((ix, iy)) => self.grid.get(ix, iy),
+            None => &self.background,
+        }
+    }
+
+    pub fn background(&self) -> &T {
+        &self.background
+    }
+
+    /// returns the range of `x` coordinates in the stored area. It may include cells which were never written.
+    pub fn x_range(&self) -> Range<i64> {
+        self.origin.x..self.origin.x + self.grid.len_x as i64
+    }
+
+    /// returns the range of `y` coordinates in the stored area. It may include cells which were never written.
+    pub fn y_range(&self) -> Range<i64> {
+        self.origin.y..self.origin.y + self.grid.len_y as i64
+    }
+
+    /// returns the dense grid holding the stored area.
+    pub fn as_grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// returns the coordinates of the element at position `(0, 0)` of the dense grid.
+    pub fn origin(&self) -> Base2d<i64> {
+        self.origin
+    }
+
+    /// returns an iterator over the positions and values in the stored area, in reading order.
+    pub fn iter_positions(&self) -> impl Iterator<Item = (Base2d<i64>, &T)> {
+        let (x_range, y_range) = (self.x_range(), self.y_range());
+        y_range
+            .flat_map(move |y| x_range.clone().map(move |x| Base2d::new(x, y)))
+            .zip(self.grid.iter())
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// translates x,y into a position of the inner grid, returning `None` if it is outside of the stored area.
+    fn inner_position(&self, x: i64, y: i64) -> Option<(usize, usize)> {
+        if self.x_range().contains(&x) && self.y_range().contains(&y) {
+            Some(((x - self.origin.x) as usize, (y - self.origin.y) as usize))
+        } else {
+            None
+        }
+    }
+}
+
+// equality and hashing follow the values returned by `get`, not the stored area, so grids with the same contents are
+// equal regardless of how they have grown
+
+impl<T: PartialEq> PartialEq for InfiniteGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        let stored_match = |a: &Self, b: &Self| a.iter_positions().all(|(pos, v)| b.get(pos.x, pos.y) == v);
+        self.background == other.background && stored_match(self, other) && stored_match(other, self)
+    }
+}
+
+impl<T: Eq> Eq for InfiniteGrid<T> {}
+
+impl<T: PartialEq + Hash> Hash for InfiniteGrid<T> {
+    /// hashes the background and the cells holding other values, in reading order.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.background.hash(state);
+        self.iter_positions()
+            .filter(|&(_, v)| *v != self.background)
+            .for_each(|cell| cell.hash(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growth() {
+        let mut grid = InfiniteGrid::new('.');
+        assert_eq!(*grid.get(-100, 100), '.');
+        assert!(grid.x_range().is_empty());
+
+        grid.update_at(0, 0, '#');
+        grid.update_at(-3, 2, 'a');
+        grid.update_at(5, -7, 'b');
+        assert_eq!(*grid.get(0, 0), '#');
+        assert_eq!(*grid.get(-3, 2), 'a');
+        assert_eq!(*grid.get(5, -7), 'b');
+        assert_eq!(*grid.get(1, 1), '.');
+        assert!(grid.x_range().contains(&-3) && grid.x_range().contains(&5));
+        assert!(grid.y_range().contains(&-7) && grid.y_range().contains(&2));
+
+        let written: String = grid.iter_positions().map(|(_, &c)| c).filter(|&c| c != '.').collect();
+        assert_eq!(written, "b#a");
+
+        grid.set_background('~');
+        assert_eq!(*grid.get(-100, 100), '~');
+        assert_eq!(*grid.get(1, 1), '~');
+        assert_eq!(*grid.get(0, 0), '#');
+        assert!(grid.as_grid().iter().all(|&c| "~#ab".contains(c)));
+    }
+
+    #[test]
+    fn test_equality() {
+        use std::collections::HashSet;
+
+        let mut a = InfiniteGrid::new(0u8);
+        a.update_at(0, 0, 1);
+        a.update_at(10, 10, 2);
+        let mut b = InfiniteGrid::new(0u8);
+        b.update_at(10, 10, 2);
+        b.update_at(0, 0, 1);
+        b.update_at(-5, 3, 0);
+        b.pad(4);
+        assert_ne!(a.x_range(), b.x_range());
+        assert_eq!(a, b);
+        assert_eq!([&a, &b].iter().collect::<HashSet<_>>().len(), 1);
+
+        b.set_background(7);
+        assert_ne!(a, b);
+        a.set_background(7);
+        assert_ne!(a, b); // (-5, 3) was written with the old background
+        a.update_at(-5, 3, 0);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,209 @@
+//! A small dense matrix of unsigned integers.
+//!
+//! Meant for linear recurrences: when a state vector evolves by a fixed linear transition, `n` steps are the product
+//! with the transition matrix raised to `n`, which takes `O(log n)` multiplications by repeated squaring.
+//!
+//! Products are accumulated in `u128`. Without a modulus, any result exceeding `u64` panics; with a modulus, every
+//! element is reduced so arbitrarily long recurrences can be computed.
+
+use std::{
+    convert::TryFrom,
+    ops::{Index, IndexMut, Mul},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Matrix {
+    flat: Vec<u64>,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Matrix {
+    /// creates a matrix filled with zeros.
+    pub fn new(rows: usize, cols: usize) -> Matrix {
+        Matrix {
+            flat: vec![0; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// creates a square matrix with ones on the diagonal.
+    pub fn identity(n: usize) -> Matrix {
+        let mut m = Matrix::new(n, n);
+        (0..n).for_each(|i| m[(i, i)] = 1);
+        m
+    }
+
+    /// creates a matrix from its rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows have different lengths.
+    pub fn from_rows(rows: Vec<Vec<u64>>) -> Matrix {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|r| r.len() == cols), "rows have different lengths");
+        Matrix {
+            rows: rows.len(),
+            cols,
+            flat: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    //------------------------------
+    // Products
+    //------------------------------
+
+    /// returns the product `self * rhs` with every element reduced modulo `modulus`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions do not match or if the modulus is zero.
+    pub fn mul_mod(&self, rhs: &Matrix, modulus: u64) -> Matrix {
+        assert_ne!(modulus, 0, "the modulus must be positive");
+        self.multiply(rhs, Some(modulus))
+    }
+
+    /// returns the matrix raised to the power `exp`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square or if any element overflows `u64`.
+    pub fn pow(&self, exp: u64) -> Matrix {
+        self.power(exp, None)
+    }
+
+    /// returns the matrix raised to the power `exp` with every element reduced modulo `modulus`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square or if the modulus is zero.
+    pub fn pow_mod(&self, exp: u64, modulus: u64) -> Matrix {
+        assert_ne!(modulus, 0, "the modulus must be positive");
+        self.power(exp, Some(modulus))
+    }
+
+    /// returns the product of the matrix by the column vector `v`, with the elements reduced modulo `modulus` if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `v` is not the amount of columns, if the modulus is zero or if any element overflows
+    /// `u64`.
+    pub fn mul_vec(&self, v: &[u64], modulus: Option<u64>) -> Vec<u64> {
+        let column = Matrix {
+            flat: v.to_vec(),
+            rows: v.len(),
+            cols: 1,
+        };
+        self.multiply(&column, modulus).flat
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    fn multiply(&self, rhs: &Matrix, modulus: Option<u64>) -> Matrix {
+        assert_eq!(self.cols, rhs.rows, "matrix dimensions do not match");
+        let mut product = Matrix::new(self.rows, rhs.cols);
+        for r in 0..self.rows {
+            for c in 0..rhs.cols {
+                let mut terms = (0..self.cols).map(|k| self[(r, k)] as u128 * rhs[(k, c)] as u128);
+                product[(r, c)] = match modulus {
+                    // each reduced term is below 2^64, so the sum of the terms can not overflow
+                    Some(m) => (terms.map(|t| t % m as u128).sum::<u128>() % m as u128) as u64,
+                    None => terms
+                        .try_fold(0u128, u128::checked_add)
+                        .and_then(|sum| u64::try_from(sum).ok())
+                        .expect("matrix element overflows u64"),
+                };
+            }
+        }
+        product
+    }
+
+    /// repeated squaring.
+    fn power(&self, mut exp: u64, modulus: Option<u64>) -> Matrix {
+        assert_eq!(self.rows, self.cols, "only square matrices can be raised to a power");
+        let mut result = Matrix::identity(self.rows);
+        if let Some(m) = modulus {
+            result.flat.iter_mut().for_each(|v| *v %= m); // the identity is zero modulo 1
+        }
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.multiply(&base, modulus);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.multiply(&base, modulus);
+            }
+        }
+        result
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = u64;
+
+    /// returns the element at `(row, column)`.
+    fn index(&self, (r, c): (usize, usize)) -> &u64 {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        &self.flat[r * self.cols + c]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut u64 {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        &mut self.flat[r * self.cols + c]
+    }
+}
+
+/// # Panics
+///
+/// Panics if the dimensions do not match or if any element overflows `u64`.
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: &Matrix) -> Matrix {
+        self.multiply(rhs, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_products() {
+        let a = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let b = Matrix::from_rows(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+        assert_eq!(&a * &b, Matrix::from_rows(vec![vec![58, 64], vec![139, 154]]));
+        assert_eq!(a.mul_mod(&b, 10), Matrix::from_rows(vec![vec![8, 4], vec![9, 4]]));
+        assert_eq!(&a * &Matrix::identity(3), a);
+        assert_eq!(a.mul_vec(&[1, 0, 2], None), vec![7, 16]);
+
+        let big = Matrix::from_rows(vec![vec![u64::MAX, u64::MAX], vec![u64::MAX, u64::MAX]]);
+        let r = u64::MAX as u128 % 1_000_000_007;
+        assert_eq!(
+            big.mul_mod(&big, 1_000_000_007)[(0, 0)] as u128,
+            2 * r * r % 1_000_000_007
+        );
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        let fib = Matrix::from_rows(vec![vec![1, 1], vec![1, 0]]);
+        assert_eq!(fib.pow(0), Matrix::identity(2));
+        assert_eq!(fib.pow(10)[(0, 1)], 55);
+        assert_eq!(fib.pow(92)[(0, 1)], 7540113804746346429); // the element (0, 0) is the largest one fitting u64
+        assert_eq!(fib.pow_mod(1_000_000_000_000, 1_000_000_007)[(0, 1)], 730_695_249);
+        assert_eq!(fib.pow_mod(5, 1), Matrix::new(2, 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow() {
+        Matrix::from_rows(vec![vec![1, 1], vec![1, 0]]).pow(93);
+    }
+}
@@ -0,0 +1,184 @@
+//! An N-dimensional array using a flat internal representation.
+//!
+//! Follows the same row major discipline as `Grid`: the first axis (`x`) varies fastest, so consecutive elements
+//! across it are next to each other, whereas every following axis is strided by the product of the lengths of the
+//! previous ones. A position is an array `[usize; N]`, e.g. `[x, y, z]` for a `Grid3d`.
+
+use super::grid::Grid;
+
+pub type Grid3d<T> = NdGrid<T, 3>;
+pub type Grid4d<T> = NdGrid<T, 4>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct NdGrid<T, const N: usize> {
+    flat: Vec<T>,
+    pub dims: [usize; N], // length of each axis
+}
+
+impl<T: Clone, const N: usize> NdGrid<T, N> {
+    /// creates a new grid with all the elements having the `init`ial value.
+    pub fn new(dims: [usize; N], init: T) -> NdGrid<T, N> {
+        NdGrid {
+            flat: vec![init; dims.iter().product()],
+            dims,
+        }
+    }
+
+    /// returns the 2D plane spanned by the axes `x_axis` and `y_axis` as a `Grid`. The coordinates along the remaining
+    /// axes are taken from `fixed`, whose values for `x_axis` and `y_axis` are ignored.
+    ///
+    /// For a `Grid3d`, `plane(0, 1, [0, 0, z])` returns the layer at height `z`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both axes are the same or if any coordinate is out of bounds.
+    pub fn plane(&self, x_axis: usize, y_axis: usize, fixed: [usize; N]) -> Grid<T> {
+        assert_ne!(x_axis, y_axis, "a plane needs two distinct axes");
+        let (len_x, len_y) = (self.dims[x_axis], self.dims[y_axis]);
+        let mut pos = fixed;
+        let mut flat = Vec::with_capacity(len_x * len_y);
+        for y in 0..len_y {
+            for x in 0..len_x {
+                pos[x_axis] = x;
+                pos[y_axis] = y;
+                flat.push(self.get(&pos).clone());
+            }
+        }
+        Grid::from_vec(len_x, len_y, flat)
+    }
+}
+
+impl<T, const N: usize> NdGrid<T, N> {
+    /// creates a grid from a flat vector in row major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `v` is not the product of the dimensions.
+    pub fn from_vec(dims: [usize; N], v: Vec<T>) -> NdGrid<T, N> {
+        assert_eq!(
+            v.len(),
+            dims.iter().product::<usize>(),
+            "vector length does not match the dimensions"
+        );
+        NdGrid { flat: v, dims }
+    }
+
+    //------------------------------
+    // Getters for single elements
+    //------------------------------
+
+    /// returns the value at position `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    pub fn get(&self, pos: &[usize; N]) -> &T {
+        &self.flat[self.index(pos)]
+    }
+
+    pub fn get_mut(&mut self, pos: &[usize; N]) -> &mut T {
+        let i = self.index(pos); // must have an aux variable coz mutable borrow
+        &mut self.flat[i]
+    }
+
+    pub fn update_at(&mut self, pos: &[usize; N], value: T) {
+        let i = self.index(pos);
+        self.flat[i] = value;
+    }
+
+    //------------------------------
+    // Getters for multiple elements
+    //------------------------------
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.flat.iter()
+    }
+
+    /// returns an iterator over all the positions of the grid, in the same order as the elements are stored.
+    pub fn positions(&self) -> impl Iterator<Item = [usize; N]> {
+        let dims = self.dims;
+        (0..self.size()).map(move |mut i| {
+            let mut pos = [0; N];
+            for (p, &len) in pos.iter_mut().zip(&dims) {
+                *p = i % len;
+                i /= len;
+            }
+            pos
+        })
+    }
+
+    /// returns an iterator over the positions adjacent to `pos` which are inside the grid, including the diagonal
+    /// ones, i.e. up to `3^N - 1` neighbours.
+    pub fn neighbours(&self, pos: [usize; N]) -> impl Iterator<Item = [usize; N]> {
+        let dims = self.dims;
+        let count = 3usize.pow(N as u32);
+        (0..count).filter(move |&k| k != count / 2).filter_map(move |mut k| {
+            // each base 3 digit of `k` encodes an offset of -1, 0 or 1 for one axis
+            let mut next = pos;
+            for (n, &len) in next.iter_mut().zip(&dims) {
+                *n = (*n + k % 3).checked_sub(1).filter(|&v| v < len)?;
+                k /= 3;
+            }
+            Some(next)
+        })
+    }
+
+    //------------------------------
+    // Helpers
+    //------------------------------
+
+    /// returns the total size of the array (product of the dimensions).
+    pub fn size(&self) -> usize {
+        self.flat.len()
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// returns the index for acessing the `flat` array from the position `pos`.
+    fn index(&self, pos: &[usize; N]) -> usize {
+        pos.iter().zip(&self.dims).rev().fold(0, |acc, (&p, &len)| {
+            assert!(p < len, "index out of bounds");
+            acc * len + p
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indexing() {
+        let grid = Grid3d::from_vec([2, 3, 4], (0..24).collect());
+        assert_eq!(*grid.get(&[1, 0, 0]), 1);
+        assert_eq!(*grid.get(&[0, 1, 0]), 2);
+        assert_eq!(*grid.get(&[1, 2, 3]), 23);
+        assert!(grid.positions().zip(grid.iter()).all(|(p, &v)| *grid.get(&p) == v));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid4d::new([3, 3, 3, 3], 0u8);
+        assert_eq!(grid.neighbours([1, 1, 1, 1]).count(), 80);
+        assert_eq!(grid.neighbours([0, 0, 0, 0]).count(), 15);
+        let grid = Grid3d::new([3, 3, 3], 0u8);
+        assert_eq!(grid.neighbours([1, 1, 1]).count(), 26);
+        assert!(grid
+            .neighbours([2, 0, 1])
+            .all(|p| p != [2, 0, 1] && p[0] >= 1 && p[1] <= 1));
+    }
+
+    #[test]
+    fn test_plane() {
+        let grid = Grid3d::from_vec([2, 3, 4], (0..24).collect());
+        let layer = grid.plane(0, 1, [0, 0, 2]);
+        assert_eq!((layer.len_x, layer.len_y), (2, 3));
+        assert_eq!(layer.iter().copied().collect::<Vec<_>>(), (12..18).collect::<Vec<_>>());
+
+        let side = grid.plane(2, 1, [1, 0, 0]);
+        assert_eq!((side.len_x, side.len_y), (4, 3));
+        assert_eq!(side.line(0), &[1, 7, 13, 19]);
+    }
+}
@@ -0,0 +1,184 @@
+//! Recognition of block letters drawn on a grid.
+//!
+//! Some puzzles produce their answer as capital letters rendered with lit cells. Two fonts are supported, chosen by the
+//! height of the drawing:
+//!
+//! - 6 rows: letters 4 columns wide, separated by 1 blank column;
+//!
+//! - 10 rows: letters 6 columns wide, separated by 2 blank columns.
+//!
+//! Blank rows and columns around the drawing are ignored.
+
+use std::{error::Error, fmt};
+
+use super::grid::Grid;
+
+/// Glyphs of the 4x6 font, rows are concatenated from top to bottom.
+const SMALL_FONT: [(char, &str); 16] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// Glyphs of the 6x10 font, rows are concatenated from top to bottom.
+const LARGE_FONT: [(char, &str); 15] = [
+    ('A', "..##...#..#.#....##....##....########....##....##....##....#"),
+    ('B', "#####.#....##....##....######.#....##....##....##....######."),
+    ('C', ".####.#....##.....#.....#.....#.....#.....#.....#....#.####."),
+    ('E', "#######.....#.....#.....#####.#.....#.....#.....#.....######"),
+    ('F', "#######.....#.....#.....#####.#.....#.....#.....#.....#....."),
+    ('G', ".####.#....##.....#.....#.....#..####....##....##...##.###.#"),
+    ('H', "#....##....##....##....########....##....##....##....##....#"),
+    ('J', "...###....#.....#.....#.....#.....#.....#.#...#.#...#..###.."),
+    ('K', "#....##...#.#..#..#.#...##....##....#.#...#..#..#...#.#....#"),
+    ('L', "#.....#.....#.....#.....#.....#.....#.....#.....#.....######"),
+    ('N', "#....###...###...##.#..##.#..##..#.##..#.##...###...###....#"),
+    ('P', "#####.#....##....##....######.#.....#.....#.....#.....#....."),
+    ('R', "#####.#....##....##....######.#..#..#...#.#...#.#....##....#"),
+    ('X', "#....##....#.#..#..#..#...##....##...#..#..#..#.#....##....#"),
+    ('Z', "######.....#.....#....#....#....#....#....#.....#.....######"),
+];
+
+/// Errors returned when the drawing can not be decoded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OcrError {
+    /// there is no font for drawings with the given amount of rows.
+    UnsupportedHeight(usize),
+    /// some glyphs do not match any letter. Holds the text decoded so far, with `?` in place of the unknown glyphs,
+    /// and the columns where each unknown glyph starts, relative to the first lit column.
+    UnknownGlyphs { decoded: String, columns: Vec<usize> },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::UnsupportedHeight(h) => write!(f, "no font for letters {} rows tall", h),
+            OcrError::UnknownGlyphs { decoded, columns } => {
+                write!(f, "unknown glyphs at columns {:?}, decoded as \"{}\"", columns, decoded)
+            }
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+/// decodes the letters drawn with the `true` cells of the `grid`.
+pub fn recognise(grid: &Grid<bool>) -> Result<String, OcrError> {
+    let lit_rows: Vec<_> = (0..grid.len_y).filter(|&y| grid.line(y).contains(&true)).collect();
+    let lit_columns: Vec<_> = (0..grid.len_x)
+        .filter(|&x| (0..grid.len_y).any(|y| *grid.get(x, y)))
+        .collect();
+    let (top, left) = match (lit_rows.first(), lit_columns.first()) {
+        (Some(&top), Some(&left)) => (top, left),
+        _ => return Ok(String::new()), // nothing drawn
+    };
+    let (bottom, right) = (*lit_rows.last().unwrap(), *lit_columns.last().unwrap());
+
+    let height = bottom - top + 1;
+    let (font, width, stride): (&[(char, &str)], _, _) = match height {
+        6 => (&SMALL_FONT, 4, 5),
+        10 => (&LARGE_FONT, 6, 8),
+        _ => return Err(OcrError::UnsupportedHeight(height)),
+    };
+
+    let mut decoded = String::new();
+    let mut unknown = Vec::new();
+    for x0 in (left..=right).step_by(stride) {
+        // cells beyond the right side of the grid are read as blank
+        let glyph: String = (top..=bottom)
+            .flat_map(|y| (x0..x0 + width).map(move |x| (x, y)))
+            .map(|(x, y)| if x < grid.len_x && *grid.get(x, y) { '#' } else { '.' })
+            .collect();
+        match font.iter().find(|(_, g)| *g == glyph) {
+            Some(&(letter, _)) => decoded.push(letter),
+            None => {
+                decoded.push('?');
+                unknown.push(x0 - left);
+            }
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(decoded)
+    } else {
+        Err(OcrError::UnknownGlyphs {
+            decoded,
+            columns: unknown,
+        })
+    }
+}
+
+/// decodes the letters drawn with the `lit` characters of a rendered `grid`, e.g. `'#'`.
+pub fn recognise_chars(grid: &Grid<char>, lit: char) -> Result<String, OcrError> {
+    recognise(&grid.map(|&c| c == lit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// renders the `text` with the font, letters separated by `gap` blank columns.
+    fn render(font: &[(char, &str)], width: usize, gap: usize, text: &str) -> Grid<char> {
+        let height = font[0].1.len() / width;
+        let glyphs: Vec<_> = text
+            .chars()
+            .map(|c| font.iter().find(|(l, _)| *l == c).map_or(&"", |(_, g)| g))
+            .collect();
+        let len_x = glyphs.len() * (width + gap);
+        let flat = (0..height)
+            .flat_map(|y| {
+                glyphs.iter().flat_map(move |g| {
+                    let line = g.chars().skip(y * width).take(width);
+                    line.chain(std::iter::repeat_n('.', gap))
+                })
+            })
+            .collect();
+        Grid::from_vec(len_x, height, flat)
+    }
+
+    #[test]
+    fn test_fonts() {
+        assert!(SMALL_FONT.iter().all(|(_, g)| g.len() == 24));
+        assert!(LARGE_FONT.iter().all(|(_, g)| g.len() == 60));
+
+        let all_small: String = SMALL_FONT.iter().map(|(c, _)| c).collect();
+        let grid = render(&SMALL_FONT, 4, 1, &all_small);
+        assert_eq!(recognise_chars(&grid, '#'), Ok(all_small));
+
+        let all_large: String = LARGE_FONT.iter().map(|(c, _)| c).collect();
+        let grid = render(&LARGE_FONT, 6, 2, &all_large);
+        assert_eq!(recognise_chars(&grid, '#'), Ok(all_large));
+    }
+
+    #[test]
+    fn test_margins_and_errors() {
+        let grid = render(&SMALL_FONT, 4, 1, "FZ").padded((3, 2), (4, 1), '.');
+        assert_eq!(recognise_chars(&grid, '#'), Ok("FZ".to_string()));
+
+        let mut grid = render(&SMALL_FONT, 4, 1, "HEL");
+        grid.update_at(6, 1, '#'); // smudges the E
+        assert_eq!(
+            recognise_chars(&grid, '#'),
+            Err(OcrError::UnknownGlyphs {
+                decoded: "H?L".to_string(),
+                columns: vec![5]
+            })
+        );
+
+        let grid = Grid::new(5, 7, true);
+        assert_eq!(recognise(&grid), Err(OcrError::UnsupportedHeight(7)));
+    }
+}
@@ -0,0 +1,237 @@
+//! Shortest path searches over a `Grid`.
+//!
+//! Positions are `(x, y)` tuples, the same coordinate system used by `Grid::get`. Moving between two cells is
+//! controlled by closures receiving the values of the cell being left (`from`) and the cell being entered (`to`):
+//!
+//! - `passable(from, to)` tells if the move is allowed;
+//!
+//! - `cost(from, to)` returns the cost of the move (only for weighted searches).
+//!
+//! Which cells are adjacent is defined by a `Connectivity`.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use super::grid::{Connectivity, Grid};
+
+/// A path found by a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub cost: u64,                  // total cost, the number of moves for unweighted searches
+    pub steps: Vec<(usize, usize)>, // positions from start to goal, both included
+}
+
+//--------------------------------------------------------------------
+// Searches
+//--------------------------------------------------------------------
+
+/// Breadth first search, every move costs 1. Returns `None` if `goal` is unreachable from `start`.
+pub fn bfs<T, P>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    connectivity: Connectivity,
+    passable: P,
+) -> Option<Path>
+where
+    P: Fn(&T, &T) -> bool,
+{
+    let mut previous = vec![None; grid.size()];
+    let mut visited = vec![false; grid.size()];
+    visited[grid.index(start.0, start.1)] = true;
+
+    let mut queue = VecDeque::from(vec![(start, 0)]);
+    while let Some((pos, dist)) = queue.pop_front() {
+        if pos == goal {
+            return Some(Path {
+                cost: dist,
+                steps: reconstruct(grid, &previous, goal),
+            });
+        }
+        for next in grid.neighbours(pos.0, pos.1, connectivity) {
+            let i = grid.index(next.0, next.1);
+            if !visited[i] && passable(grid.get(pos.0, pos.1), grid.get(next.0, next.1)) {
+                visited[i] = true;
+                previous[i] = Some(pos);
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra's algorithm, each move costs `cost(from, to)`. Returns `None` if `goal` is unreachable from `start`.
+pub fn dijkstra<T, P, C>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    connectivity: Connectivity,
+    passable: P,
+    cost: C,
+) -> Option<Path>
+where
+    P: Fn(&T, &T) -> bool,
+    C: Fn(&T, &T) -> u64,
+{
+    astar(grid, start, goal, connectivity, passable, cost, |_| 0)
+}
+
+/// A* search, each move costs `cost(from, to)` and `heuristic(pos)` estimates the remaining cost from `pos` to
+/// `goal`. Returns `None` if `goal` is unreachable from `start`.
+///
+/// The path is only guaranteed to be the shortest if the heuristic never overestimates the remaining cost, e.g. the
+/// manhattan distance when all moves cost at least 1.
+pub fn astar<T, P, C, H>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    connectivity: Connectivity,
+    passable: P,
+    cost: C,
+    heuristic: H,
+) -> Option<Path>
+where
+    P: Fn(&T, &T) -> bool,
+    C: Fn(&T, &T) -> u64,
+    H: Fn((usize, usize)) -> u64,
+{
+    let mut previous = vec![None; grid.size()];
+    let mut best = vec![u64::MAX; grid.size()];
+    best[grid.index(start.0, start.1)] = 0;
+
+    // the heap is ordered by estimated total cost, then by the cost so far
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(start), 0, start)));
+    while let Some(Reverse((_, dist, pos))) = heap.pop() {
+        if pos == goal {
+            return Some(Path {
+                cost: dist,
+                steps: reconstruct(grid, &previous, goal),
+            });
+        }
+        if dist > best[grid.index(pos.0, pos.1)] {
+            continue; // stale entry
+        }
+
+        let from = grid.get(pos.0, pos.1);
+        for next in grid.neighbours(pos.0, pos.1, connectivity) {
+            let to = grid.get(next.0, next.1);
+            if !passable(from, to) {
+                continue;
+            }
+            let i = grid.index(next.0, next.1);
+            let next_dist = dist + cost(from, to);
+            if next_dist < best[i] {
+                best[i] = next_dist;
+                previous[i] = Some(pos);
+                heap.push(Reverse((next_dist + heuristic(next), next_dist, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Breadth first search from one or many `sources` at once. Returns a grid with the number of moves from the nearest
+/// source to each cell, or `None` for unreachable cells.
+pub fn distance_map<T, I, P>(grid: &Grid<T>, sources: I, connectivity: Connectivity, passable: P) -> Grid<Option<usize>>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+    P: Fn(&T, &T) -> bool,
+{
+    let mut distances = Grid::new(grid.len_x, grid.len_y, None);
+    let mut queue = VecDeque::new();
+    for (x, y) in sources {
+        distances.update_at(x, y, Some(0));
+        queue.push_back((x, y));
+    }
+
+    while let Some((x, y)) = queue.pop_front() {
+        let dist = distances.get(x, y).unwrap();
+        for (nx, ny) in grid.neighbours(x, y, connectivity) {
+            if distances.get(nx, ny).is_none() && passable(grid.get(x, y), grid.get(nx, ny)) {
+                distances.update_at(nx, ny, Some(dist + 1));
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    distances
+}
+
+//--------------------------------------------------------------------
+// Helpers
+//--------------------------------------------------------------------
+
+/// walks back the `previous` links from `goal`, returning the positions from the start to the goal.
+fn reconstruct<T>(grid: &Grid<T>, previous: &[Option<(usize, usize)>], goal: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut steps = vec![goal];
+    let mut pos = goal;
+    while let Some(prev) = previous[grid.index(pos.0, pos.1)] {
+        steps.push(prev);
+        pos = prev;
+    }
+    steps.reverse();
+    steps
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_from(s: &str) -> Grid<u8> {
+        Grid::from_lines(s, |c| c as u8)
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = grid_from("..#.\n.##.\n....\n");
+        let open = |_: &u8, &to: &u8| to != b'#';
+        let path = bfs(&maze, (0, 0), (3, 0), Connectivity::Four, open).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.steps.len(), 8);
+        assert_eq!(path.steps.first(), Some(&(0, 0)));
+        assert_eq!(path.steps.last(), Some(&(3, 0)));
+        assert!(bfs(&maze, (0, 0), (2, 0), Connectivity::Four, open).is_none());
+    }
+
+    #[test]
+    fn test_weighted() {
+        let risks = grid_from(
+            "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n\
+             1319128137\n1359912421\n3125421639\n1293138521\n2311944581\n",
+        );
+        let risk = |_: &u8, &to: &u8| u64::from(to - b'0');
+        let goal = (9, 9);
+
+        let path = dijkstra(&risks, (0, 0), goal, Connectivity::Four, |_, _| true, risk).unwrap();
+        assert_eq!(path.cost, 40);
+
+        let manhattan = |(x, y): (usize, usize)| (goal.0 - x + goal.1 - y) as u64;
+        let path = astar(&risks, (0, 0), goal, Connectivity::Four, |_, _| true, risk, manhattan).unwrap();
+        assert_eq!(path.cost, 40);
+        let total: u64 = path.steps[1..]
+            .iter()
+            .map(|&(x, y)| u64::from(risks.get(x, y) - b'0'))
+            .sum();
+        assert_eq!(total, 40);
+    }
+
+    #[test]
+    fn test_distance_map() {
+        let maze = grid_from("..#.\n.##.\n....\n");
+        let open = |_: &u8, &to: &u8| to != b'#';
+        let distances = distance_map(&maze, vec![(0, 0), (3, 0)], Connectivity::Four, open);
+        assert_eq!(*distances.get(0, 2), Some(2));
+        assert_eq!(*distances.get(3, 2), Some(2));
+        assert_eq!(*distances.get(2, 2), Some(3));
+        assert_eq!(*distances.get(2, 0), None);
+    }
+}
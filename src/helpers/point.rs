@@ -0,0 +1,337 @@
+//! A point with any amount of dimensions.
+//!
+//! `Point<T, N>` is the N-dimensional counterpart of `Base2d`: it supports the same comma separated parsing, tuple
+//! conversions and arithmetic, storing the coordinates in an array. `Base3d` is the three-dimensional alias, which
+//! can also be rotated into any of the 24 axis-aligned orientations.
+
+use std::{
+    convert::{TryFrom, TryInto},
+    error::Error,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::base2d::Base2d;
+
+pub type Base3d<T> = Point<T, 3>;
+
+/// The ordering compares the coordinates in order, i.e: the first axis first.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+impl<T: Copy, const N: usize> Point<T, N> {
+    pub fn new(coords: [T; N]) -> Point<T, N> {
+        Point(coords)
+    }
+
+    /// returns a point with all the coordinates set to `value`.
+    pub fn splat(value: T) -> Point<T, N> {
+        Point([value; N])
+    }
+
+    /// returns a point by applying `f` to each coordinate.
+    pub fn map<U, F: FnMut(T) -> U>(&self, f: F) -> Point<U, N> {
+        Point(self.0.map(f))
+    }
+
+    /// returns a point by applying `f` to each pair of coordinates of both points.
+    pub fn zip_with<U: Copy, V, F>(&self, rhs: &Point<U, N>, mut f: F) -> Point<V, N>
+    where
+        F: FnMut(T, U) -> V,
+    {
+        Point(std::array::from_fn(|i| f(self.0[i], rhs.0[i])))
+    }
+}
+
+impl<T: Copy> Point<T, 3> {
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+}
+
+//--------------------------------------------------------------------
+// Distances
+//--------------------------------------------------------------------
+
+impl<T, const N: usize> Point<T, N>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T>,
+{
+    /// returns the absolute difference of each coordinate, see `Base2d::abs_diff`.
+    pub fn abs_diff(&self, rhs: &Self) -> Self {
+        self.zip_with(rhs, |a, b| if a > b { a - b } else { b - a })
+    }
+
+    /// returns the sum of the differences between coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point has no dimensions.
+    pub fn manhattan(&self, rhs: &Self) -> T {
+        self.abs_diff(rhs).0.iter().copied().reduce(|acc, d| acc + d).unwrap()
+    }
+
+    /// returns the largest difference between coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point has no dimensions.
+    pub fn chebyshev(&self, rhs: &Self) -> T {
+        self.abs_diff(rhs).0.iter().copied().max().unwrap()
+    }
+}
+
+//--------------------------------------------------------------------
+// Rotations
+//--------------------------------------------------------------------
+
+/// One of the 24 axis-aligned orientations of the space, i.e: a permutation of the axes, each one possibly reversed,
+/// which does not mirror the space. The coordinate `i` of a rotated point is `signs[i] * p[axes[i]]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    pub axes: [usize; 3],
+    pub signs: [i8; 3],
+}
+
+impl Rotation {
+    /// the rotation which leaves every point in place.
+    pub const IDENTITY: Rotation = Rotation {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    /// returns all the 24 orientations, starting with the identity.
+    pub fn all() -> Vec<Rotation> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        let mut rotations = Vec::with_capacity(24);
+        for &axes in PERMUTATIONS.iter() {
+            // an odd permutation mirrors the space, so it must be compensated by an odd amount of reversed axes
+            let inversions = (0..3)
+                .flat_map(|i| (i + 1..3).map(move |j| (i, j)))
+                .filter(|&(i, j)| axes[i] > axes[j])
+                .count();
+            let parity = if inversions % 2 == 0 { 1 } else { -1 };
+            for mask in 0..8 {
+                let signs = [0, 1, 2].map(|i| if mask & (1 << i) == 0 { 1 } else { -1 });
+                if signs.iter().product::<i8>() == parity {
+                    rotations.push(Rotation { axes, signs });
+                }
+            }
+        }
+        rotations
+    }
+
+    /// returns the rotation which undoes this one.
+    pub fn inverse(&self) -> Rotation {
+        let mut inverse = Rotation::IDENTITY;
+        for i in 0..3 {
+            inverse.axes[self.axes[i]] = i;
+            inverse.signs[self.axes[i]] = self.signs[i];
+        }
+        inverse
+    }
+}
+
+impl<T> Point<T, 3>
+where
+    T: Copy + Neg<Output = T>,
+{
+    pub fn rotate(&self, rotation: &Rotation) -> Self {
+        Point([0, 1, 2].map(|i| {
+            let v = self.0[rotation.axes[i]];
+            if rotation.signs[i] < 0 {
+                -v
+            } else {
+                v
+            }
+        }))
+    }
+
+    /// returns an iterator over the point in each of the 24 orientations, in the same order as `Rotation::all`.
+    pub fn rotations(&self) -> impl Iterator<Item = Self> + '_ {
+        Rotation::all().into_iter().map(move |r| self.rotate(&r))
+    }
+}
+
+//--------------------------------------------------------------------
+// Arithmetic
+//--------------------------------------------------------------------
+
+impl<T: Copy + Add<Output = T>, const N: usize> Add for Point<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a + b)
+    }
+}
+
+impl<T: Copy + Sub<Output = T>, const N: usize> Sub for Point<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a - b)
+    }
+}
+
+impl<T: Copy + Neg<Output = T>, const N: usize> Neg for Point<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.map(|v| -v)
+    }
+}
+
+/// scalar multiplication
+impl<T: Copy + Mul<Output = T>, const N: usize> Mul<T> for Point<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|v| v * rhs)
+    }
+}
+
+/// scalar division
+impl<T: Copy + Div<Output = T>, const N: usize> Div<T> for Point<T, N> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        self.map(|v| v / rhs)
+    }
+}
+
+impl<T: AddAssign, const N: usize> AddAssign for Point<T, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<T: SubAssign, const N: usize> SubAssign for Point<T, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a -= b);
+    }
+}
+
+//--------------------------------------------------------------------
+// Conversions
+//--------------------------------------------------------------------
+
+impl<T, const N: usize> From<[T; N]> for Point<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        Point(coords)
+    }
+}
+
+impl<T> From<Base2d<T>> for Point<T, 2> {
+    fn from(item: Base2d<T>) -> Self {
+        Point([item.x, item.y])
+    }
+}
+
+impl<U> TryFrom<(U, U)> for Point<usize, 2>
+where
+    U: TryInto<usize>,
+    <U as TryInto<usize>>::Error: std::error::Error + 'static,
+{
+    type Error = Box<dyn Error>;
+
+    fn try_from(item: (U, U)) -> Result<Self, Self::Error> {
+        Ok(Point([item.0.try_into()?, item.1.try_into()?]))
+    }
+}
+
+impl<U> TryFrom<(U, U, U)> for Point<usize, 3>
+where
+    U: TryInto<usize>,
+    <U as TryInto<usize>>::Error: std::error::Error + 'static,
+{
+    type Error = Box<dyn Error>;
+
+    fn try_from(item: (U, U, U)) -> Result<Self, Self::Error> {
+        Ok(Point([item.0.try_into()?, item.1.try_into()?, item.2.try_into()?]))
+    }
+}
+
+impl<T, const N: usize> FromStr for Point<T, N>
+where
+    T: FromStr + Copy + Default,
+    <T as FromStr>::Err: std::error::Error + 'static,
+{
+    type Err = Box<dyn Error>;
+
+    /// string needs to have `N` values separated by commas (','). Example: "15,-21,3"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut coords = [T::default(); N];
+        let mut iter = s.split(',');
+        for c in coords.iter_mut() {
+            *c = iter
+                .next()
+                .ok_or_else(|| format!("Expected {} values separated by commas.", N))?
+                .trim()
+                .parse::<T>()?;
+        }
+        if iter.next().is_some() {
+            return Err(format!("Expected only {} values separated by commas.", N).into());
+        }
+        Ok(Point(coords))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsing_and_arithmetic() {
+        let p: Base3d<i32> = "404,-588,-901".parse().unwrap();
+        assert_eq!((p.x(), p.y(), p.z()), (404, -588, -901));
+        assert!("1,2".parse::<Base3d<i32>>().is_err());
+        assert!("1,2,3,4".parse::<Base3d<i32>>().is_err());
+        assert_eq!(Point::try_from((1i64, 2, 3)).unwrap(), Point([1usize, 2, 3]));
+        assert!(Point::<usize, 2>::try_from((-1i64, 2)).is_err());
+
+        let mut q = Point([1, 2, 3]);
+        assert_eq!(p + q, Point([405, -586, -898]));
+        assert_eq!(p - q, Point([403, -590, -904]));
+        assert_eq!(-q * 2, Point([-2, -4, -6]));
+        q += Point::splat(1);
+        q -= Point([0, 0, 4]);
+        assert_eq!(q, Point([2, 3, 0]));
+        assert_eq!(q.manhattan(&Point([0, 5, 1])), 5);
+        assert_eq!(Point([1u8, 9, 4, 4]).chebyshev(&Point([3, 2, 4, 0])), 7);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+
+        let p = Point([1, 2, 3]);
+        let mut images: Vec<_> = p.rotations().collect();
+        images.sort();
+        images.dedup();
+        assert_eq!(images.len(), 24);
+
+        let (x, y, z) = (Point([1, 0, 0]), Point([0, 1, 0]), Point([0, 0, 1]));
+        let cross = |a: Base3d<i32>, b: Base3d<i32>| {
+            Point([
+                a.0[1] * b.0[2] - a.0[2] * b.0[1],
+                a.0[2] * b.0[0] - a.0[0] * b.0[2],
+                a.0[0] * b.0[1] - a.0[1] * b.0[0],
+            ])
+        };
+        for r in rotations.iter() {
+            assert_eq!(p.rotate(r).rotate(&r.inverse()), p);
+            // no mirroring: the rotated axes are still right-handed
+            assert_eq!(cross(x.rotate(r), y.rotate(r)), z.rotate(r));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Rasterisation of line segments into grid positions.
+//!
+//! Uses Bresenham's algorithm, so segments of any slope yield every position they cross, a single one per step along
+//! the major axis. Horizontal, vertical and 45° segments yield exactly the positions between both endpoints.
+
+use super::base2d::Base2d;
+
+/// Iterator over the positions of a line segment, from the first endpoint to the second one, both included.
+///
+/// Bresenham's algorithm is not symmetric: for some slopes, walking a segment in the opposite direction may yield
+/// slightly different positions. Order the endpoints beforehand if the result must not depend on the direction.
+#[derive(Debug, Clone)]
+pub struct Bresenham {
+    pos: Base2d<i64>,
+    end: Base2d<i64>,
+    delta: Base2d<i64>, // (|dx|, -|dy|)
+    step: Base2d<i64>,  // direction of each axis, -1 or 1
+    err: i64,
+    done: bool,
+}
+
+impl Bresenham {
+    pub fn new(p0: Base2d<i64>, p1: Base2d<i64>) -> Bresenham {
+        let delta = Base2d::new((p1.x - p0.x).abs(), -(p1.y - p0.y).abs());
+        let step = Base2d::new(if p0.x < p1.x { 1 } else { -1 }, if p0.y < p1.y { 1 } else { -1 });
+        Bresenham {
+            pos: p0,
+            end: p1,
+            delta,
+            step,
+            err: delta.x + delta.y,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Bresenham {
+    type Item = Base2d<i64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let current = self.pos;
+        if current == self.end {
+            self.done = true;
+            return Some(current);
+        }
+
+        let e2 = 2 * self.err;
+        if e2 >= self.delta.y {
+            self.err += self.delta.y;
+            self.pos.x += self.step.x;
+        }
+        if e2 <= self.delta.x {
+            self.err += self.delta.x;
+            self.pos.y += self.step.y;
+        }
+        Some(current)
+    }
+}
+
+/// returns an iterator over the positions of the segment from `p0` to `p1`, both included.
+pub fn line<U>(p0: &Base2d<U>, p1: &Base2d<U>) -> Bresenham
+where
+    U: Copy + Into<i64>,
+{
+    Bresenham::new(
+        Base2d::new(p0.x.into(), p0.y.into()),
+        Base2d::new(p1.x.into(), p1.y.into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(p0: (i64, i64), p1: (i64, i64)) -> Vec<(i64, i64)> {
+        line(&Base2d::new(p0.0, p0.1), &Base2d::new(p1.0, p1.1))
+            .map(|p| p.tuple())
+            .collect()
+    }
+
+    #[test]
+    fn test_straight_and_diagonal() {
+        assert_eq!(points((2, 1), (2, 4)), vec![(2, 1), (2, 2), (2, 3), (2, 4)]);
+        assert_eq!(points((3, 0), (0, 0)), vec![(3, 0), (2, 0), (1, 0), (0, 0)]);
+        assert_eq!(points((0, 3), (3, 0)), vec![(0, 3), (1, 2), (2, 1), (3, 0)]);
+        assert_eq!(points((5, 5), (5, 5)), vec![(5, 5)]);
+    }
+
+    #[test]
+    fn test_any_slope() {
+        assert_eq!(points((0, 0), (4, 2)), vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+        assert_eq!(points((0, 0), (-1, -5)).len(), 6);
+        let steep = points((1, -3), (-2, 7));
+        assert_eq!(steep.len(), 11);
+        assert_eq!(steep.last(), Some(&(-2, 7)));
+        // each step moves at most one cell in each axis
+        assert!(steep
+            .windows(2)
+            .all(|w| (w[0].0 - w[1].0).abs() <= 1 && (w[0].1 - w[1].1).abs() == 1));
+    }
+}
@@ -0,0 +1,301 @@
+//! Axis-aligned rectangles and cuboids.
+//!
+//! A rectangle is defined by two corners, `min` (top left) and `max` (bottom right), both included. Coordinates follow
+//! the same convention as `Grid`: `x` is the column and `y` the row. A `Cuboid` is the same idea in three dimensions,
+//! with the corners stored as `Base3d` points.
+//!
+//! Since both corners are included, a box always holds at least one position. The sizes are computed as
+//! `max - min + 1`, so unsigned types are supported as well.
+
+use std::ops::{Add, Mul, Sub};
+
+use super::{base2d::Base2d, point::Base3d};
+
+//--------------------------------------------------------------------
+// Rect
+//--------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rect<U> {
+    pub min: Base2d<U>, // top left corner, included
+    pub max: Base2d<U>, // bottom right corner, included
+}
+
+impl<U: Copy + Ord> Rect<U> {
+    /// creates a rectangle from any two opposite corners.
+    pub fn new(p0: Base2d<U>, p1: Base2d<U>) -> Rect<U> {
+        Rect {
+            min: p0.component_min(&p1),
+            max: p0.component_max(&p1),
+        }
+    }
+
+    /// returns the smallest rectangle containing all the `points`, or `None` if there are no points.
+    pub fn from_points<I: IntoIterator<Item = Base2d<U>>>(points: I) -> Option<Rect<U>> {
+        let mut iter = points.into_iter();
+        let first = iter.next()?;
+        Some(iter.fold(Rect::new(first, first), |rect, p| Rect {
+            min: rect.min.component_min(&p),
+            max: rect.max.component_max(&p),
+        }))
+    }
+
+    /// returns `true` if the point `p` is inside the rectangle or on its border.
+    pub fn contains(&self, p: &Base2d<U>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// returns `true` if every position of `other` is inside this rectangle.
+    pub fn contains_rect(&self, other: &Rect<U>) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    /// returns the positions shared by both rectangles, or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Rect<U>) -> Option<Rect<U>> {
+        let min = self.min.component_max(&other.min);
+        let max = self.max.component_min(&other.max);
+        if min.x <= max.x && min.y <= max.y {
+            Some(Rect { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// returns the smallest rectangle containing both rectangles.
+    pub fn union_bounds(&self, other: &Rect<U>) -> Rect<U> {
+        Rect {
+            min: self.min.component_min(&other.min),
+            max: self.max.component_max(&other.max),
+        }
+    }
+}
+
+impl<U> Rect<U>
+where
+    U: Copy + Ord + Add<Output = U> + Sub<Output = U> + Mul<Output = U> + From<u8>,
+{
+    /// returns the amount of columns in the rectangle.
+    pub fn width(&self) -> U {
+        self.max.x - self.min.x + U::from(1)
+    }
+
+    /// returns the amount of rows in the rectangle.
+    pub fn height(&self) -> U {
+        self.max.y - self.min.y + U::from(1)
+    }
+
+    /// returns the amount of cells in the rectangle.
+    pub fn area(&self) -> U {
+        self.width() * self.height()
+    }
+
+    /// returns the positions of this rectangle which are not in `other`, as up to 4 disjoint rectangles: the full
+    /// width bands above and below `other`, and the pieces to its left and right.
+    pub fn subtract(&self, other: &Rect<U>) -> Vec<Rect<U>> {
+        let inter = match self.intersection(other) {
+            Some(inter) => inter,
+            None => return vec![*self],
+        };
+        let one = U::from(1);
+        let mut pieces = Vec::with_capacity(4);
+        if self.min.y < inter.min.y {
+            pieces.push(Rect {
+                min: self.min,
+                max: Base2d::new(self.max.x, inter.min.y - one),
+            });
+        }
+        if inter.max.y < self.max.y {
+            pieces.push(Rect {
+                min: Base2d::new(self.min.x, inter.max.y + one),
+                max: self.max,
+            });
+        }
+        if self.min.x < inter.min.x {
+            pieces.push(Rect {
+                min: Base2d::new(self.min.x, inter.min.y),
+                max: Base2d::new(inter.min.x - one, inter.max.y),
+            });
+        }
+        if inter.max.x < self.max.x {
+            pieces.push(Rect {
+                min: Base2d::new(inter.max.x + one, inter.min.y),
+                max: Base2d::new(self.max.x, inter.max.y),
+            });
+        }
+        pieces
+    }
+}
+
+impl Rect<usize> {
+    /// returns an iterator over the positions `(x, y)` inside the rectangle, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| (x, y)))
+    }
+}
+
+//--------------------------------------------------------------------
+// Cuboid
+//--------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cuboid<U> {
+    pub min: Base3d<U>, // corner with the smallest coordinates, included
+    pub max: Base3d<U>, // corner with the largest coordinates, included
+}
+
+impl<U: Copy + Ord> Cuboid<U> {
+    /// creates a cuboid from any two opposite corners.
+    pub fn new(p0: Base3d<U>, p1: Base3d<U>) -> Cuboid<U> {
+        Cuboid {
+            min: p0.zip_with(&p1, U::min),
+            max: p0.zip_with(&p1, U::max),
+        }
+    }
+
+    /// returns the smallest cuboid containing all the `points`, or `None` if there are no points.
+    pub fn from_points<I: IntoIterator<Item = Base3d<U>>>(points: I) -> Option<Cuboid<U>> {
+        let mut iter = points.into_iter();
+        let first = iter.next()?;
+        Some(iter.fold(Cuboid::new(first, first), |cuboid, p| Cuboid {
+            min: cuboid.min.zip_with(&p, U::min),
+            max: cuboid.max.zip_with(&p, U::max),
+        }))
+    }
+
+    /// returns `true` if the point `p` is inside the cuboid or on its surface.
+    pub fn contains(&self, p: &Base3d<U>) -> bool {
+        (0..3).all(|i| (self.min.0[i]..=self.max.0[i]).contains(&p.0[i]))
+    }
+
+    /// returns `true` if every position of `other` is inside this cuboid.
+    pub fn contains_cuboid(&self, other: &Cuboid<U>) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    /// returns the positions shared by both cuboids, or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Cuboid<U>) -> Option<Cuboid<U>> {
+        let min = self.min.zip_with(&other.min, U::max);
+        let max = self.max.zip_with(&other.max, U::min);
+        if (0..3).all(|i| min.0[i] <= max.0[i]) {
+            Some(Cuboid { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// returns the smallest cuboid containing both cuboids.
+    pub fn union_bounds(&self, other: &Cuboid<U>) -> Cuboid<U> {
+        Cuboid {
+            min: self.min.zip_with(&other.min, U::min),
+            max: self.max.zip_with(&other.max, U::max),
+        }
+    }
+}
+
+impl<U> Cuboid<U>
+where
+    U: Copy + Ord + Add<Output = U> + Sub<Output = U> + Mul<Output = U> + From<u8>,
+{
+    /// returns the amount of positions along each axis.
+    pub fn lengths(&self) -> Base3d<U> {
+        self.max.zip_with(&self.min, |max, min| max - min + U::from(1))
+    }
+
+    /// returns the amount of positions in the cuboid.
+    pub fn volume(&self) -> U {
+        let lengths = self.lengths();
+        lengths.x() * lengths.y() * lengths.z()
+    }
+
+    /// returns the positions of this cuboid which are not in `other`, as up to 6 disjoint cuboids.
+    pub fn subtract(&self, other: &Cuboid<U>) -> Vec<Cuboid<U>> {
+        let inter = match self.intersection(other) {
+            Some(inter) => inter,
+            None => return vec![*self],
+        };
+        // cuts the slabs below and above the intersection along each axis, the remainder shrinks to the intersection
+        let one = U::from(1);
+        let mut remainder = *self;
+        let mut pieces = Vec::with_capacity(6);
+        for axis in 0..3 {
+            if remainder.min.0[axis] < inter.min.0[axis] {
+                let mut piece = remainder;
+                piece.max.0[axis] = inter.min.0[axis] - one;
+                pieces.push(piece);
+            }
+            if inter.max.0[axis] < remainder.max.0[axis] {
+                let mut piece = remainder;
+                piece.min.0[axis] = inter.max.0[axis] + one;
+                pieces.push(piece);
+            }
+            remainder.min.0[axis] = inter.min.0[axis];
+            remainder.max.0[axis] = inter.max.0[axis];
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::point::Point;
+
+    #[test]
+    fn test_rect_operations() {
+        let points = [(3, 7), (-2, 4), (5, 5)].iter().map(|&(x, y)| Base2d::new(x, y));
+        let bounds = Rect::from_points(points).unwrap();
+        assert_eq!(bounds, Rect::new(Base2d::new(5, 4), Base2d::new(-2, 7)));
+        assert_eq!((bounds.width(), bounds.height(), bounds.area()), (8, 4, 32));
+        assert_eq!(Rect::<i32>::from_points(Vec::new()), None);
+
+        let other = Rect::new(Base2d::new(0, 0), Base2d::new(9, 5));
+        let inter = bounds.intersection(&other).unwrap();
+        assert_eq!(inter, Rect::new(Base2d::new(0, 4), Base2d::new(5, 5)));
+        assert!(bounds.contains_rect(&inter) && other.contains_rect(&inter));
+        assert_eq!(
+            bounds.union_bounds(&other),
+            Rect::new(Base2d::new(-2, 0), Base2d::new(9, 7))
+        );
+        assert_eq!(
+            bounds.intersection(&Rect::new(Base2d::new(6, 0), Base2d::new(9, 9))),
+            None
+        );
+
+        let pieces = bounds.subtract(&other);
+        assert_eq!(pieces.iter().map(Rect::area).sum::<i32>(), bounds.area() - inter.area());
+        for (i, a) in pieces.iter().enumerate() {
+            assert!(bounds.contains_rect(a) && a.intersection(&other).is_none());
+            assert!(pieces[i + 1..].iter().all(|b| a.intersection(b).is_none()));
+        }
+        assert_eq!(inter.subtract(&bounds), vec![]);
+    }
+
+    #[test]
+    fn test_cuboid_reactor() {
+        // example of the reactor reboot puzzle: the volume turned on after a sequence of steps on cuboids
+        let steps = [
+            (true, [10, 10, 10], [12, 12, 12]),
+            (true, [11, 11, 11], [13, 13, 13]),
+            (false, [9, 9, 9], [11, 11, 11]),
+            (true, [10, 10, 10], [10, 10, 10]),
+        ];
+        let mut on: Vec<Cuboid<i64>> = Vec::new();
+        for &(turn_on, p0, p1) in steps.iter() {
+            let cuboid = Cuboid::new(Point(p0), Point(p1));
+            on = on.iter().flat_map(|c| c.subtract(&cuboid)).collect();
+            if turn_on {
+                on.push(cuboid);
+            }
+        }
+        assert_eq!(on.iter().map(Cuboid::volume).sum::<i64>(), 39);
+
+        let cuboid = Cuboid::from_points(vec![Point([1u32, 5, 2]), Point([3, 0, 2])]).unwrap();
+        assert_eq!(cuboid.lengths(), Point([3, 6, 1]));
+        assert!(cuboid.contains(&Point([2, 3, 2])) && !cuboid.contains(&Point([2, 3, 3])));
+        let big = cuboid.union_bounds(&Cuboid::new(Point([0, 0, 0]), Point([1, 1, 9])));
+        assert_eq!(big.volume(), 4 * 6 * 10);
+        assert!(big.contains_cuboid(&cuboid));
+        assert_eq!(big.subtract(&cuboid).iter().map(Cuboid::volume).sum::<u32>(), 240 - 18);
+    }
+}
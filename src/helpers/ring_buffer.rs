@@ -0,0 +1,160 @@
+//! A circular buffer with a fixed capacity.
+//!
+//! The elements are stored in an array and a `head` index marks where the logical sequence starts, so rotating the
+//! buffer only moves the index instead of the elements. Indexing, iteration and comparisons all follow the logical
+//! order, starting from the head.
+
+use std::{
+    hash::{Hash, Hasher},
+    mem,
+    ops::{Index, IndexMut},
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct RingBuffer<T, const N: usize> {
+    buf: [T; N],
+    head: usize, // position in `buf` of the logical index 0
+}
+
+impl<T: Copy, const N: usize> RingBuffer<T, N> {
+    /// creates a new buffer with all the elements having the `init`ial value.
+    pub fn new(init: T) -> RingBuffer<T, N> {
+        RingBuffer::from([init; N])
+    }
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// moves the head `n` positions forward: the element at logical index `n` becomes the first one and the first
+    /// `n` elements go to the end.
+    pub fn rotate_left(&mut self, n: usize) {
+        if N > 0 {
+            self.head = (self.head + n % N) % N;
+        }
+    }
+
+    /// moves the head `n` positions backward: the last `n` elements become the first ones.
+    pub fn rotate_right(&mut self, n: usize) {
+        if N > 0 {
+            self.head = (self.head + N - n % N) % N;
+        }
+    }
+
+    /// appends `value` at the end, overwriting the first element, which is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer has no capacity.
+    pub fn push(&mut self, value: T) -> T {
+        let old = mem::replace(&mut self.buf[self.head], value);
+        self.rotate_left(1);
+        old
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.buf.get(self.head)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        N.checked_sub(1).map(|last| &self[last])
+    }
+
+    /// returns an iterator over the elements in logical order, starting from the head.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let (tail, head) = self.buf.split_at(self.head);
+        head.iter().chain(tail)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let (tail, head) = self.buf.split_at_mut(self.head);
+        head.iter_mut().chain(tail)
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// returns the index for acessing `buf` from the logical index `i`.
+    fn index(&self, i: usize) -> usize {
+        assert!(i < N, "index out of bounds");
+        (self.head + i) % N
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for RingBuffer<T, N> {
+    /// the first element of the array becomes the head.
+    fn from(buf: [T; N]) -> Self {
+        RingBuffer { buf, head: 0 }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for RingBuffer<T, N> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    fn index(&self, i: usize) -> &T {
+        &self.buf[RingBuffer::index(self, i)]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for RingBuffer<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let i = RingBuffer::index(self, i); // must have an aux variable coz mutable borrow
+        &mut self.buf[i]
+    }
+}
+
+// equality and hashing follow the logical order, buffers with the same sequence but different heads are equal
+
+impl<T: PartialEq, const N: usize> PartialEq for RingBuffer<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for RingBuffer<T, N> {}
+
+impl<T: Hash, const N: usize> Hash for RingBuffer<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.iter().for_each(|v| v.hash(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_and_indexing() {
+        let mut ring = RingBuffer::from([0, 1, 2, 3, 4]);
+        ring.rotate_left(2);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4, 0, 1]);
+        assert_eq!((ring[0], ring[4]), (2, 1));
+        ring[3] += 10;
+        ring.rotate_right(7); // same as 2
+        assert_eq!(ring, RingBuffer::from([10, 1, 2, 3, 4]));
+        assert_eq!((ring.front(), ring.back()), (Some(&10), Some(&4)));
+
+        ring.iter_mut().for_each(|v| *v *= 2);
+        assert_eq!(ring.push(7), 20);
+        assert_eq!(ring.push(8), 2);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![4, 6, 8, 7, 8]);
+        assert_eq!(ring.len(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_bounds() {
+        let ring = RingBuffer::<u8, 3>::new(0);
+        let _ = ring[3];
+    }
+}
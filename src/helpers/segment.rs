@@ -0,0 +1,233 @@
+//! Line segments on the integer lattice.
+//!
+//! A segment goes from `p0` to `p1`, both included. Its lattice points are the positions with integer coordinates lying
+//! exactly on it, which for horizontal, vertical and 45° segments are the same positions yielded by `raster::line`.
+//! Other slopes skip some columns or rows, e.g. `(0, 0) -> (4, 2)` only has the lattice points `(0, 0)`, `(2, 1)` and
+//! `(4, 2)`.
+//!
+//! Intersections are computed exactly with integer arithmetic, without rasterising the segments into a grid.
+
+use std::convert::TryFrom;
+
+use super::{base2d::Base2d, rect::Rect, utils::number_theory::gcd};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub p0: Base2d<i64>,
+    pub p1: Base2d<i64>,
+}
+
+impl Segment {
+    pub fn new(p0: Base2d<i64>, p1: Base2d<i64>) -> Segment {
+        Segment { p0, p1 }
+    }
+
+    //------------------------------
+    // Orientation
+    //------------------------------
+
+    pub fn is_horizontal(&self) -> bool {
+        self.p0.is_same_row(&self.p1)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.p0.is_same_column(&self.p1)
+    }
+
+    /// returns `true` if the segment is either horizontal or vertical.
+    pub fn is_straight(&self) -> bool {
+        self.is_horizontal() || self.is_vertical()
+    }
+
+    /// returns `true` if the segment is at 45°. A single point is not diagonal.
+    pub fn is_diagonal(&self) -> bool {
+        let d = self.p0.abs_diff(&self.p1);
+        d.x == d.y && d.x != 0
+    }
+
+    /// returns `true` if both endpoints are the same.
+    pub fn is_point(&self) -> bool {
+        self.p0 == self.p1
+    }
+
+    //------------------------------
+    // Geometry
+    //------------------------------
+
+    /// returns the smallest rectangle containing the segment.
+    pub fn bounding_box(&self) -> Rect<i64> {
+        Rect::new(self.p0, self.p1)
+    }
+
+    /// returns the step between consecutive lattice points, from `p0` towards `p1`, or `(0, 0)` for a single point.
+    pub fn step(&self) -> Base2d<i64> {
+        let d = self.p1 - self.p0;
+        match gcd(d.x, d.y) {
+            0 => d,
+            g => d / g,
+        }
+    }
+
+    /// returns `true` if the point `p` lies exactly on the segment.
+    pub fn contains(&self, p: &Base2d<i64>) -> bool {
+        cross(self.p1 - self.p0, *p - self.p0) == 0 && self.bounding_box().contains(p)
+    }
+
+    /// returns an iterator over the lattice points of the segment, from `p0` to `p1`.
+    pub fn lattice_points(&self) -> impl Iterator<Item = Base2d<i64>> {
+        let (p0, step) = (self.p0, self.step());
+        (0..=self.lattice_steps()).map(move |k| p0 + step * k)
+    }
+
+    /// returns the lattice points shared by both segments, ordered from `self.p0` towards `self.p1`.
+    ///
+    /// Crossing segments share at most one point, which may not be in the lattice, whereas collinear segments may
+    /// overlap along several points.
+    pub fn intersection(&self, other: &Segment) -> Vec<Base2d<i64>> {
+        if self.is_point() {
+            return if other.contains(&self.p0) {
+                vec![self.p0]
+            } else {
+                Vec::new()
+            };
+        }
+        if other.is_point() {
+            return if self.contains(&other.p0) {
+                vec![other.p0]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let (d0, d1) = (self.p1 - self.p0, other.p1 - other.p0);
+        let offset = other.p0 - self.p0;
+        let denom = cross(d0, d1);
+
+        if denom == 0 {
+            if cross(d0, offset) != 0 {
+                return Vec::new(); // parallel
+            }
+            // collinear: each point of `other` is `self.p0 + step * k`, the common ones are in both ranges of `k`
+            let step = self.step();
+            let k_of = |p: Base2d<i64>| dot(p - self.p0, step) / dot(step, step);
+            let (ka, kb) = (k_of(other.p0), k_of(other.p1));
+            let k_min = ka.min(kb).max(0);
+            let k_max = ka.max(kb).min(self.lattice_steps() as i128);
+            return (k_min..=k_max)
+                .map(|k| self.p0 + step * i64::try_from(k).unwrap())
+                .collect();
+        }
+
+        // crossing: the point is `self.p0 + d0 * t / denom`, for `t` in [0, denom] (or [denom, 0] if negative)
+        let (t, u) = (cross(offset, d1), cross(offset, d0));
+        let within = |v: i128| {
+            if denom > 0 {
+                (0..=denom).contains(&v)
+            } else {
+                (denom..=0).contains(&v)
+            }
+        };
+        if !within(t) || !within(u) {
+            return Vec::new();
+        }
+        let (x, y) = (
+            self.p0.x as i128 * denom + d0.x as i128 * t,
+            self.p0.y as i128 * denom + d0.y as i128 * t,
+        );
+        if x % denom != 0 || y % denom != 0 {
+            return Vec::new(); // the segments cross between lattice points
+        }
+        vec![Base2d::new((x / denom) as i64, (y / denom) as i64)]
+    }
+
+    //------------------------------
+    // Private
+    //------------------------------
+
+    /// returns the amount of steps between both endpoints, i.e: the amount of lattice points minus one.
+    fn lattice_steps(&self) -> i64 {
+        let d = self.p1 - self.p0;
+        gcd(d.x, d.y)
+    }
+}
+
+impl<U: Copy + Into<i64>> From<(Base2d<U>, Base2d<U>)> for Segment {
+    fn from((p0, p1): (Base2d<U>, Base2d<U>)) -> Self {
+        Segment::new(
+            Base2d::new(p0.x.into(), p0.y.into()),
+            Base2d::new(p1.x.into(), p1.y.into()),
+        )
+    }
+}
+
+//--------------------------------------------------------------------
+// Helpers
+//--------------------------------------------------------------------
+
+/// z component of the cross product, using `i128` so the products can not overflow.
+fn cross(a: Base2d<i64>, b: Base2d<i64>) -> i128 {
+    a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128
+}
+
+fn dot(a: Base2d<i64>, b: Base2d<i64>) -> i128 {
+    a.x as i128 * b.x as i128 + a.y as i128 * b.y as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(x0: i64, y0: i64, x1: i64, y1: i64) -> Segment {
+        Segment::new(Base2d::new(x0, y0), Base2d::new(x1, y1))
+    }
+
+    fn points(v: Vec<Base2d<i64>>) -> Vec<(i64, i64)> {
+        v.iter().map(Base2d::tuple).collect()
+    }
+
+    #[test]
+    fn test_geometry() {
+        let s = seg(0, 0, 4, 2);
+        assert!(!s.is_straight() && !s.is_diagonal());
+        assert!(seg(3, 1, 0, 4).is_diagonal());
+        assert!(seg(3, 1, 3, 9).is_vertical());
+        assert_eq!(points(s.lattice_points().collect()), vec![(0, 0), (2, 1), (4, 2)]);
+        assert!(s.contains(&Base2d::new(2, 1)));
+        assert!(!s.contains(&Base2d::new(1, 1)));
+        assert!(!s.contains(&Base2d::new(6, 3)));
+        assert_eq!(
+            seg(5, -1, 2, 3).bounding_box(),
+            Rect::new(Base2d::new(2, -1), Base2d::new(5, 3))
+        );
+        assert_eq!(seg(2, 2, 2, 2).lattice_points().count(), 1);
+    }
+
+    #[test]
+    fn test_intersection() {
+        // crossing
+        assert_eq!(points(seg(0, 0, 4, 4).intersection(&seg(0, 4, 4, 0))), vec![(2, 2)]);
+        assert_eq!(points(seg(0, 0, 3, 3).intersection(&seg(0, 3, 3, 0))), vec![]); // at (1.5, 1.5)
+        assert_eq!(points(seg(0, 0, 2, 0).intersection(&seg(3, -1, 3, 1))), vec![]); // too short
+        assert_eq!(points(seg(0, 2, 6, 2).intersection(&seg(6, 2, 9, 5))), vec![(6, 2)]); // endpoints
+
+        // collinear
+        assert_eq!(
+            points(seg(0, 9, 5, 9).intersection(&seg(2, 9, 0, 9))),
+            vec![(0, 9), (1, 9), (2, 9)]
+        );
+        assert_eq!(
+            points(seg(8, 2, 2, 8).intersection(&seg(4, 6, 9, 1))),
+            vec![(8, 2), (7, 3), (6, 4), (5, 5), (4, 6)]
+        );
+        assert_eq!(
+            points(seg(0, 0, 4, 2).intersection(&seg(8, 4, 2, 1))),
+            vec![(2, 1), (4, 2)]
+        );
+        assert_eq!(points(seg(0, 0, 1, 1).intersection(&seg(2, 2, 3, 3))), vec![]);
+        assert_eq!(points(seg(0, 0, 1, 1).intersection(&seg(1, 0, 2, 1))), vec![]); // parallel
+
+        // single points
+        assert_eq!(points(seg(1, 1, 1, 1).intersection(&seg(0, 0, 2, 2))), vec![(1, 1)]);
+        assert_eq!(points(seg(0, 0, 2, 2).intersection(&seg(1, 0, 1, 0))), vec![]);
+    }
+}
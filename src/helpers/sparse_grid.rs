@@ -0,0 +1,131 @@
+//! A two-dimensional storage backed by a `HashMap`.
+//!
+//! Only the cells which have been written are stored, every other position holds the `default` value. Useful when
+//! the coordinates are too large for allocating a dense `Grid`, but only a few of the cells are ever touched.
+//!
+//! Uses the same `x, y` coordinate system as `Grid` and implements the `Grid2d` trait, so both may be used
+//! interchangeably.
+
+use std::{collections::HashMap, ops::AddAssign};
+
+use super::{base2d::Base2d, grid::Grid2d};
+
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Base2d<usize>, T>,
+    default: T,
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// creates an empty grid where every position holds the `default` value.
+    pub fn new(default: T) -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+        }
+    }
+
+    /// returns a mutable reference to the value at position x,y. The position is stored with the default value if it
+    /// has not been written before.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        let default = &self.default;
+        self.cells.entry(Base2d::new(x, y)).or_insert_with(|| default.clone())
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// returns the value at position x,y, or the default value if the position has not been written.
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        self.cells.get(&Base2d::new(x, y)).unwrap_or(&self.default)
+    }
+
+    pub fn update_at(&mut self, x: usize, y: usize, value: T) {
+        self.cells.insert(Base2d::new(x, y), value);
+    }
+
+    /// returns an iterator over the written values, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    /// returns an iterator over the written positions and their values, in arbitrary order.
+    pub fn iter_positions(&self) -> impl Iterator<Item = (Base2d<usize>, &T)> {
+        self.cells.iter().map(|(&pos, v)| (pos, v))
+    }
+
+    /// returns the number of written positions.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+impl<T: Clone + AddAssign> SparseGrid<T> {
+    pub fn increment_by(&mut self, x: usize, y: usize, value: T) {
+        *self.get_mut(x, y) += value;
+    }
+}
+
+impl<T: Clone> Grid2d<T> for SparseGrid<T> {
+    fn get(&self, x: usize, y: usize) -> &T {
+        SparseGrid::get(self, x, y)
+    }
+
+    fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        SparseGrid::get_mut(self, x, y)
+    }
+
+    fn values<'a>(&'a self) -> Box<dyn Iterator<Item = &'a T> + 'a> {
+        Box::new(self.iter())
+    }
+}
+
+/// grids are equal when every position holds the same value, so a position written with the default value is the same
+/// as one never written.
+impl<T: PartialEq> PartialEq for SparseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        let written_match = |a: &Self, b: &Self| a.cells.iter().all(|(pos, v)| b.get(pos.x, pos.y) == v);
+        self.default == other.default && written_match(self, other) && written_match(other, self)
+    }
+}
+
+impl<T: Eq> Eq for SparseGrid<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new(0u16);
+        assert_eq!(*grid.get(500_000, 800_000), 0);
+        assert!(grid.is_empty());
+
+        grid.increment_by(500_000, 800_000, 2);
+        *grid.get_mut(500_000, 800_000) += 1;
+        grid.update_at(3, 4, 7);
+        assert_eq!(*grid.get(500_000, 800_000), 3);
+        assert_eq!(*grid.get(3, 4), 7);
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.iter().sum::<u16>(), 10);
+    }
+
+    #[test]
+    fn test_equality() {
+        let mut a = SparseGrid::new(0u8);
+        let mut b = SparseGrid::new(0u8);
+        a.update_at(1, 2, 5);
+        b.update_at(1, 2, 5);
+        b.update_at(7, 7, 0);
+        let _ = b.get_mut(9, 9);
+        assert_eq!(a, b);
+        assert_eq!(b, a);
+
+        b.update_at(7, 7, 1);
+        assert_ne!(a, b);
+        assert_ne!(SparseGrid::new(0u8), SparseGrid::new(1u8));
+    }
+}
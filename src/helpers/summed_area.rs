@@ -0,0 +1,90 @@
+//! Summed-area table (two-dimensional prefix sums) of a `Grid`.
+//!
+//! After an O(n) construction, the sum of the values inside any rectangle is computed in O(1). The table holds an extra
+//! row and column of zeros (`T::default()`), so no bounds special cases are needed when querying.
+
+use std::ops::{Add, Sub};
+
+use super::{grid::Grid, rect::Rect};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SummedAreaTable<T> {
+    table: Grid<T>, // the value at x,y is the sum of the grid values in the rectangle (0, 0) to (x - 1, y - 1)
+}
+
+impl<T> SummedAreaTable<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new(grid: &Grid<T>) -> SummedAreaTable<T> {
+        let mut table = Grid::new(grid.len_x + 1, grid.len_y + 1, T::default());
+        for y in 0..grid.len_y {
+            for x in 0..grid.len_x {
+                // the column above first, so unsigned types neither underflow nor overflow before the final sum
+                let sum = *table.get(x, y + 1) - *table.get(x, y) + *table.get(x + 1, y) + *grid.get(x, y);
+                table.update_at(x + 1, y + 1, sum);
+            }
+        }
+        SummedAreaTable { table }
+    }
+
+    /// returns the sum of the values inside the `rect`angle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle is not inside the grid.
+    pub fn sum(&self, rect: &Rect<usize>) -> T {
+        let (min, max) = (rect.min, rect.max);
+        // the table is one element larger on each axis than the grid
+        assert!(max.x < self.table.len_x - 1 && max.y < self.table.len_y - 1);
+        // the columns of the rectangle from the top, minus the same columns above the rectangle
+        let columns = *self.table.get(max.x + 1, max.y + 1) - *self.table.get(min.x, max.y + 1);
+        let above = *self.table.get(max.x + 1, min.y) - *self.table.get(min.x, min.y);
+        columns - above
+    }
+
+    /// returns the sum of all values in the grid.
+    pub fn total(&self) -> T {
+        *self.table.get(self.table.len_x - 1, self.table.len_y - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::base2d::Base2d;
+
+    #[test]
+    fn test_sums() {
+        let grid = Grid::from_vec(4, 3, (1u32..=12).collect());
+        let table = SummedAreaTable::new(&grid);
+        assert_eq!(table.total(), 78);
+
+        let rects = [((0, 0), (0, 0)), ((1, 1), (2, 2)), ((3, 0), (0, 2)), ((2, 0), (3, 2))];
+        for &(p0, p1) in &rects {
+            let rect = Rect::new(Base2d::new(p0.0, p0.1), Base2d::new(p1.0, p1.1));
+            let expected: u32 = grid.rect_iter(&rect).sum();
+            assert_eq!(table.sum(&rect), expected);
+        }
+    }
+
+    #[test]
+    fn test_no_overflow() {
+        // every sum fits in `u8`, but adding the overlapping rectangles first would not
+        let grid = Grid::from_vec(2, 2, vec![60u8, 60, 60, 60]);
+        let table = SummedAreaTable::new(&grid);
+        assert_eq!(table.total(), 240);
+        assert_eq!(table.sum(&Rect::new(Base2d::new(1, 1), Base2d::new(1, 1))), 60);
+        assert_eq!(table.sum(&Rect::new(Base2d::new(0, 1), Base2d::new(1, 1))), 120);
+
+        let table = SummedAreaTable::new(&Grid::from_vec(3, 1, vec![0u8, u8::MAX, 0]));
+        assert_eq!(table.sum(&Rect::new(Base2d::new(1, 0), Base2d::new(2, 0))), u8::MAX);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_bounds() {
+        let table = SummedAreaTable::new(&Grid::new(4, 3, 1u32));
+        table.sum(&Rect::new(Base2d::new(0, 0), Base2d::new(4, 0)));
+    }
+}
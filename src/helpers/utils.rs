@@ -21,73 +21,365 @@ pub fn pairs_zip<T>(input: &[T]) -> impl Iterator<Item = (&T, &T)> {
     iter1.zip(iter2)
 }
 
-/// An enum to allow subtracting negative deltas or steps from unsigned numbers.
-/// based on Matklad code, available on <https://internals.rust-lang.org/t/representing-difference-between-unsigned-integers/13563/12>
+/// Moving unsigned numbers by signed offsets, i.e: steps which can go in either direction.
 ///
-/// # Warnings
-/// - The caller must make sure the subtractions or additions will not overflow.
-pub mod delta {
-    use std::ops::{AddAssign, Sub};
-    #[derive(Copy, Clone)]
-    pub enum Delta<T> {
-        Add(T),
-        Sub(T),
-    }
-
-    impl<T: Ord + Sub<Output = T>> Delta<T> {
-        pub fn new(old: T, new: T, step: T) -> Delta<T> {
-            if new > old {
-                Delta::Add(step)
-            } else {
-                Delta::Sub(step)
-            }
+/// The operations are the ones from the standard library (`checked_add_signed` and its siblings), gathered in a trait
+/// so they can be used by generic code, like `Base2d<U>`, for any unsigned integer.
+pub mod offset {
+    use std::convert::TryFrom;
+
+    /// An unsigned integer which can be moved by an offset of the signed type with the same width.
+    pub trait SignedOffset: Copy {
+        type Signed: Copy;
+
+        /// returns `self + delta`, or `None` if the result is out of the range of the type.
+        fn checked_offset(self, delta: Self::Signed) -> Option<Self>;
+
+        /// returns `self + delta`, wrapping around at the boundaries of the type.
+        fn wrapping_offset(self, delta: Self::Signed) -> Self;
+
+        /// returns `self + delta`, clamped to the range of the type.
+        fn saturating_offset(self, delta: Self::Signed) -> Self;
+
+        /// returns the offset which moves `self` to `target`, or `None` if it does not fit the signed type.
+        fn checked_delta(self, target: Self) -> Option<Self::Signed>;
+    }
+
+    macro_rules! impl_signed_offset {
+        ($($unsigned:ty => $signed:ty),*) => {
+            $(
+                impl SignedOffset for $unsigned {
+                    type Signed = $signed;
+
+                    fn checked_offset(self, delta: $signed) -> Option<Self> {
+                        self.checked_add_signed(delta)
+                    }
+
+                    fn wrapping_offset(self, delta: $signed) -> Self {
+                        self.wrapping_add_signed(delta)
+                    }
+
+                    fn saturating_offset(self, delta: $signed) -> Self {
+                        self.saturating_add_signed(delta)
+                    }
+
+                    fn checked_delta(self, target: Self) -> Option<$signed> {
+                        if target >= self {
+                            <$signed>::try_from(target - self).ok()
+                        } else {
+                            // shifted by one so the distance to the minimum value still fits
+                            <$signed>::try_from(self - target - 1).ok().map(|d| -d - 1)
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_signed_offset!(u8 => i8, u16 => i16, u32 => i32, u64 => i64, u128 => i128, usize => isize);
+}
+
+/// Number theory for the integer types up to 64 bits.
+///
+/// The values are converted to `i128` for the computations, so intermediate products and sums can not overflow.
+/// Results which do not fit back into the original type are reported with `None` or a panic, as documented by each
+/// function.
+pub mod number_theory {
+    use std::convert::TryFrom;
+
+    /// An integer type which fits in an `i128`.
+    pub trait Integer: Copy + Ord {
+        fn to_i128(self) -> i128;
+
+        /// returns `None` if the value is out of the range of the type.
+        fn from_i128(value: i128) -> Option<Self>;
+    }
+
+    macro_rules! impl_integer {
+        ($($t:ty),*) => {
+            $(
+                impl Integer for $t {
+                    fn to_i128(self) -> i128 {
+                        self as i128
+                    }
+
+                    fn from_i128(value: i128) -> Option<Self> {
+                        <$t>::try_from(value).ok()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+    /// converts a result back into `T`, which only fails for values that can not be represented by the type.
+    fn back<T: Integer>(value: i128) -> T {
+        T::from_i128(value).expect("result out of the range of the type")
+    }
+
+    /// returns `a * b % m` for `a` and `b` in `[0, m)`, the product is computed as `u128` since it may exceed `i128`
+    /// for 64 bits moduli.
+    fn mul_mod(a: i128, b: i128, m: i128) -> i128 {
+        ((a as u128 * b as u128) % m as u128) as i128
+    }
+
+    //------------------------------
+    // Divisors and multiples
+    //------------------------------
+
+    /// greatest common divisor, always non-negative. `gcd(0, 0)` is `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result does not fit the type, i.e: `gcd(i64::MIN, 0)`.
+    pub fn gcd<T: Integer>(a: T, b: T) -> T {
+        back(gcd_i128(a.to_i128(), b.to_i128()))
+    }
+
+    fn gcd_i128(a: i128, b: i128) -> i128 {
+        let (mut a, mut b) = (a.abs(), b.abs());
+        while b != 0 {
+            let r = a % b;
+            a = b;
+            b = r;
         }
+        a
     }
 
-    // This won't be coherent :-(
-    // impl<T: AddAssign + SubAssign> AddAssign<Delta<T>> for T
-    impl AddAssign<Delta<usize>> for usize {
-        fn add_assign(&mut self, rhs: Delta<usize>) {
-            match rhs {
-                Delta::Add(amt) => *self += amt,
-                Delta::Sub(amt) => *self -= amt,
-            }
+    /// least common multiple, always non-negative. It is `0` if any value is `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result does not fit the type.
+    pub fn lcm<T: Integer>(a: T, b: T) -> T {
+        back(lcm_i128(a.to_i128(), b.to_i128()).expect("result out of the range of the type"))
+    }
+
+    fn lcm_i128(a: i128, b: i128) -> Option<i128> {
+        match gcd_i128(a, b) {
+            0 => Some(0),
+            g => (a / g).abs().checked_mul(b.abs()),
         }
     }
 
-    impl AddAssign<Delta<u64>> for u64 {
-        fn add_assign(&mut self, rhs: Delta<u64>) {
-            match rhs {
-                Delta::Add(amt) => *self += amt,
-                Delta::Sub(amt) => *self -= amt,
-            }
+    /// greatest common divisor of all the values, `0` for an empty iterator.
+    pub fn gcd_iter<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+        back(values.into_iter().fold(0, |acc, v| gcd_i128(acc, v.to_i128())))
+    }
+
+    /// least common multiple of all the values, `1` for an empty iterator. Useful for finding when several cycles
+    /// line up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result does not fit the type.
+    pub fn lcm_iter<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+        let lcm = values.into_iter().try_fold(1, |acc, v| lcm_i128(acc, v.to_i128()));
+        back(lcm.expect("result out of the range of the type"))
+    }
+
+    /// extended Euclidean algorithm: returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is `gcd(a, b)`.
+    ///
+    /// The coefficients may be negative even for unsigned inputs, so all the values are returned as `i128`.
+    pub fn extended_gcd<T: Integer>(a: T, b: T) -> (i128, i128, i128) {
+        extended_gcd_i128(a.to_i128(), b.to_i128())
+    }
+
+    fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+        let (mut r0, mut r1) = (a, b);
+        let (mut x0, mut x1) = (1, 0);
+        let (mut y0, mut y1) = (0, 1);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (x0, x1) = (x1, x0 - q * x1);
+            (y0, y1) = (y1, y0 - q * y1);
+        }
+        if r0 < 0 {
+            (-r0, -x0, -y0)
+        } else {
+            (r0, x0, y0)
+        }
+    }
+
+    //------------------------------
+    // Modular arithmetic
+    //------------------------------
+
+    /// returns `x` in `[0, m)` such that `a * x % m == 1`, or `None` if `a` and `m` are not coprime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` is not positive.
+    pub fn mod_inverse<T: Integer>(a: T, m: T) -> Option<T> {
+        let m_128 = m.to_i128();
+        assert!(m_128 > 0, "the modulus must be positive");
+        let (g, x, _) = extended_gcd_i128(a.to_i128().rem_euclid(m_128), m_128);
+        if g == 1 {
+            Some(back(x.rem_euclid(m_128)))
+        } else {
+            None
         }
     }
 
-    impl AddAssign<Delta<u32>> for u32 {
-        fn add_assign(&mut self, rhs: Delta<u32>) {
-            match rhs {
-                Delta::Add(amt) => *self += amt,
-                Delta::Sub(amt) => *self -= amt,
+    /// returns `base^exp % m` in `[0, m)`, by repeated squaring.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` is not positive or `exp` is negative.
+    pub fn mod_pow<T: Integer>(base: T, exp: T, m: T) -> T {
+        let m = m.to_i128();
+        assert!(m > 0, "the modulus must be positive");
+        let mut exp = u64::try_from(exp.to_i128()).expect("the exponent must not be negative");
+        let mut base = base.to_i128().rem_euclid(m);
+        let mut result = 1 % m;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = mul_mod(result, base, m);
             }
+            base = mul_mod(base, base, m);
+            exp >>= 1;
         }
+        back(result)
     }
 
-    impl AddAssign<Delta<u16>> for u16 {
-        fn add_assign(&mut self, rhs: Delta<u16>) {
-            match rhs {
-                Delta::Add(amt) => *self += amt,
-                Delta::Sub(amt) => *self -= amt,
+    /// Chinese Remainder Theorem: finds `x` such that `x % m == r` for each pair `(r, m)` of the `congruences`.
+    ///
+    /// Returns `(x, lcm)` with `x` in `[0, lcm)`, where `lcm` is the least common multiple of the moduli, so every
+    /// solution is `x + k * lcm`. The moduli do not need to be coprime. Returns `None` if the congruences contradict
+    /// each other or if `lcm` does not fit the type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any modulus is not positive.
+    pub fn crt<T, I>(congruences: I) -> Option<(T, T)>
+    where
+        T: Integer,
+        I: IntoIterator<Item = (T, T)>,
+    {
+        let (mut x, mut lcm) = (0i128, 1i128);
+        for (r, m) in congruences {
+            let (r, m) = (r.to_i128(), m.to_i128());
+            assert!(m > 0, "the modulus must be positive");
+            let r = r.rem_euclid(m);
+
+            // x + lcm * k == r (mod m) => (lcm / g) * k == (r - x) / g (mod m / g)
+            let (g, inv, _) = extended_gcd_i128(lcm, m);
+            if (r - x) % g != 0 {
+                return None;
             }
+            let m_g = m / g;
+            let k = mul_mod(((r - x) / g).rem_euclid(m_g), inv.rem_euclid(m_g), m_g);
+            let next_lcm = (lcm / g).checked_mul(m).filter(|&l| T::from_i128(l).is_some())?;
+            x = (x + lcm * k).rem_euclid(next_lcm);
+            lcm = next_lcm;
         }
+        Some((back(x), back(lcm)))
     }
 
-    impl AddAssign<Delta<u8>> for u8 {
-        fn add_assign(&mut self, rhs: Delta<u8>) {
-            match rhs {
-                Delta::Add(amt) => *self += amt,
-                Delta::Sub(amt) => *self -= amt,
+    //------------------------------
+    // Roots and primes
+    //------------------------------
+
+    /// integer square root, i.e: the largest `r` such that `r * r <= n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is negative.
+    pub fn isqrt<T: Integer>(n: T) -> T {
+        let n = u128::try_from(n.to_i128()).expect("square root of a negative number");
+        back(n.isqrt() as i128)
+    }
+
+    /// returns all the prime numbers up to `n` (included), using the sieve of Eratosthenes.
+    pub fn primes_up_to(n: usize) -> Vec<usize> {
+        let mut is_prime = vec![true; n + 1];
+        let mut primes = Vec::new();
+        for i in 2..=n {
+            if is_prime[i] {
+                primes.push(i);
+                (i * i..=n).step_by(i).for_each(|j| is_prime[j] = false);
             }
         }
+        primes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{number_theory::*, offset::SignedOffset};
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0i8, 0), 0);
+        assert_eq!(lcm(4usize, 6), 12);
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(lcm(0u8, 7), 0);
+        assert_eq!(gcd_iter(vec![24u64, 60, 36]), 12);
+        assert_eq!(lcm_iter(vec![3u64, 4, 5, 6]), 60);
+        assert_eq!(lcm_iter(Vec::<u8>::new()), 1);
+
+        let (g, x, y) = extended_gcd(240u32, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lcm_overflow() {
+        lcm(200u8, 201);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inverse(3u32, 11), Some(4));
+        assert_eq!(mod_inverse(-3i32, 11), Some(7));
+        assert_eq!(mod_inverse(6u32, 9), None);
+        assert_eq!(mod_inverse(2u64, u64::MAX), Some(u64::MAX / 2 + 1));
+        assert_eq!(mod_pow(2u64, 10, 1000), 24);
+        assert_eq!(mod_pow(-2i64, 3, 5), 2);
+        assert_eq!(mod_pow(7u64, 0, 1), 0);
+        // the squares exceed `i128` for moduli close to `u64::MAX`
+        let m = u64::MAX - 58; // the largest 64 bits prime
+        assert_eq!(mod_pow(3u64, m - 1, m), 1);
+
+        assert_eq!(crt(vec![(2u64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(vec![(1i32, 4), (3, 6)]), Some((9, 12))); // not coprime
+        assert_eq!(crt(vec![(1i32, 4), (2, 6)]), None); // contradiction
+        assert_eq!(crt(vec![(0u8, 16), (0, 17)]), None); // 272 does not fit
+        assert_eq!(crt(Vec::<(u32, u32)>::new()), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_roots_and_primes() {
+        assert_eq!(isqrt(24u32), 4);
+        assert_eq!(isqrt(25i64), 5);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_up_to(1), vec![]);
+    }
+
+    #[test]
+    fn test_signed_offset() {
+        assert_eq!(5u8.checked_offset(-5), Some(0));
+        assert_eq!(5u8.checked_offset(-6), None);
+        assert_eq!(250u8.checked_offset(6), None);
+        assert_eq!(5u8.wrapping_offset(-6), 255);
+        assert_eq!(5u8.saturating_offset(-6), 0);
+        assert_eq!(250u8.saturating_offset(100), 255);
+        assert_eq!(
+            7usize.checked_offset(isize::MAX).map(|v| v - 7),
+            Some(isize::MAX as usize)
+        );
+
+        assert_eq!(3u16.checked_delta(10), Some(7));
+        assert_eq!(10u16.checked_delta(3), Some(-7));
+        assert_eq!(128u8.checked_delta(0), Some(-128));
+        assert_eq!(129u8.checked_delta(0), None);
+        assert_eq!(0u8.checked_delta(128), None);
+        assert_eq!(u64::MAX.checked_delta(0), None);
     }
 }
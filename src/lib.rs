@@ -11,8 +11,26 @@ pub mod day07;
 // pub mod day08;
 
 pub mod helpers {
+    pub mod automaton;
     pub mod base2d;
+    pub mod bigint;
+    pub mod bitgrid;
+    pub mod cycle;
+    pub mod direction;
+    pub mod graph;
     pub mod grid;
+    pub mod infinite_grid;
+    pub mod matrix;
+    pub mod ndgrid;
+    pub mod ocr;
+    pub mod pathfinding;
+    pub mod point;
+    pub mod raster;
     pub mod read;
+    pub mod rect;
+    pub mod ring_buffer;
+    pub mod segment;
+    pub mod sparse_grid;
+    pub mod summed_area;
     pub mod utils;
 }
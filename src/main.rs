@@ -17,6 +17,9 @@ fn main() {
         6 => day06::run(),
         7 => day07::run(),
         55 => day05::second_implementation::run(),
+        56 => day05::run_by_intersection(),
+        66 => day06::run_matrix(),
+        67 => day06::run_exact(),
         _ => println!("Invalid input argument."),
     }
 }